@@ -0,0 +1,363 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::async_runtime::block_on;
+use crate::commands::file::{append_to_note, set_note_content};
+use crate::commands::window::{
+    create_note, delete_note, find_note_data, focus_note, hide_note, list_notes, note_summary, set_note_color,
+    set_note_font,
+};
+use crate::models::ApiConfig;
+use crate::palette::resolve_note_color;
+
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const MAX_HEADER_LINES: usize = 64;
+/// Limit on the request line and headers together
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct RunningServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+}
+
+// The running API server, if the API is enabled
+static SERVER: once_cell::sync::Lazy<Mutex<Option<RunningServer>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+/// An HTTP status with a JSON body
+struct HttpResponse {
+    status: u16,
+    body: Value,
+}
+
+impl HttpResponse {
+    fn ok(body: Value) -> Self {
+        HttpResponse { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        HttpResponse { status, body: json!({ "error": message.into() }) }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NoteBody {
+    content: Option<String>,
+    color: Option<String>,
+    font_size: Option<u32>,
+    font_family: Option<String>,
+    line_height: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppendBody {
+    text: String,
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Compares tokens in a time that depends only on their lengths, so response times don't reveal
+/// how much of a guessed token is right
+fn tokens_match(given: &str, expected: &str) -> bool {
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+    given.len() == expected.len() && given.iter().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Reads one line of the request line or headers, taking its length off the bytes they may still use
+fn read_header_line(reader: &mut impl BufRead, remaining: &mut usize) -> Result<String, HttpResponse> {
+    let mut line = String::new();
+    let read = reader
+        .by_ref()
+        .take(*remaining as u64)
+        .read_line(&mut line)
+        .map_err(|e| HttpResponse::error(400, e.to_string()))?;
+    if read == *remaining && !line.ends_with('\n') {
+        return Err(HttpResponse::error(431, "Request headers too large"));
+    }
+    *remaining -= read;
+    Ok(line)
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<HttpRequest, HttpResponse> {
+    let bad_request = |e: std::io::Error| HttpResponse::error(400, e.to_string());
+    let mut remaining = MAX_HEADER_BYTES;
+
+    let request_line = read_header_line(reader, &mut remaining)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(HttpResponse::error(400, "Malformed request line"));
+    };
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    for _ in 0..MAX_HEADER_LINES {
+        let line = read_header_line(reader, &mut remaining)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).map_err(bad_request)?;
+            return Ok(HttpRequest { method: method.to_string(), path, authorization, body });
+        }
+
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(HttpResponse::error(400, "Malformed header"));
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "authorization" => authorization = Some(value.to_string()),
+            "content-length" => {
+                content_length = value.parse().map_err(|_| HttpResponse::error(400, "Invalid Content-Length"))?;
+                if content_length > MAX_BODY_BYTES {
+                    return Err(HttpResponse::error(413, "Request body too large"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(HttpResponse::error(400, "Too many headers"))
+}
+
+fn write_response(stream: &mut TcpStream, response: &HttpResponse) -> std::io::Result<()> {
+    let body = response.body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason_phrase(response.status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn parse_body<T: for<'de> Deserialize<'de> + Default>(body: &[u8]) -> Result<T, HttpResponse> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body).map_err(|e| HttpResponse::error(400, format!("Invalid JSON body: {}", e)))
+}
+
+fn parse_color(color: Option<String>) -> Result<Option<String>, HttpResponse> {
+    color
+        .map(|value| resolve_note_color(&value).ok_or_else(|| HttpResponse::error(400, format!("Unknown color: {}", value))))
+        .transpose()
+}
+
+/// Runs a command and maps its error to a server error
+fn respond(result: Result<Value, String>) -> HttpResponse {
+    match result {
+        Ok(body) => HttpResponse::ok(body),
+        Err(e) => HttpResponse::error(500, e),
+    }
+}
+
+fn handle_note_request(app: &tauri::AppHandle, request: &HttpRequest, id: &str, action: Option<&str>) -> Result<HttpResponse, HttpResponse> {
+    let note = find_note_data(id).ok_or_else(|| HttpResponse::error(404, format!("Note not found: {}", id)))?;
+    let app = app.clone();
+    let id = id.to_string();
+
+    let response = match (request.method.as_str(), action) {
+        ("GET", None) => {
+            let content = std::fs::read_to_string(&note.file_path).unwrap_or_default();
+            let mut body = serde_json::to_value(note_summary(&note)).map_err(|e| HttpResponse::error(500, e.to_string()))?;
+            body["content"] = Value::String(content);
+            HttpResponse::ok(body)
+        }
+        ("PATCH", None) => {
+            let body: NoteBody = parse_body(&request.body)?;
+            let color = parse_color(body.color)?;
+            let NoteBody { content, font_size, font_family, line_height, .. } = body;
+            respond(block_on(async {
+                if let Some(content) = content {
+                    set_note_content(app.clone(), id.clone(), content).await?;
+                }
+                if let Some(color) = color {
+                    set_note_color(app.clone(), id.clone(), color).await?;
+                }
+                if font_size.is_some() || font_family.is_some() || line_height.is_some() {
+                    set_note_font(app.clone(), id.clone(), font_size, font_family, line_height).await?;
+                }
+                Ok(json!({ "id": id }))
+            }))
+        }
+        ("DELETE", None) => respond(block_on(delete_note(app, id.clone())).map(|_| json!({ "id": id }))),
+        ("POST", Some("append")) => {
+            let body: AppendBody = serde_json::from_slice(&request.body)
+                .map_err(|e| HttpResponse::error(400, format!("Invalid JSON body: {}", e)))?;
+            respond(block_on(append_to_note(app, id.clone(), body.text)).map(|_| json!({ "id": id })))
+        }
+        ("POST", Some("focus")) => respond(block_on(focus_note(app, id.clone())).map(|_| json!({ "id": id }))),
+        ("POST", Some("hide")) => respond(block_on(hide_note(app, id.clone())).map(|_| json!({ "id": id }))),
+        (_, None | Some("append" | "focus" | "hide")) => HttpResponse::error(405, "Method not allowed"),
+        _ => HttpResponse::error(404, "Not found"),
+    };
+    Ok(response)
+}
+
+/// Dispatches an authenticated request:
+///
+/// - `GET /notes`, `POST /notes` `{content, color}`
+/// - `GET|PATCH|DELETE /notes/{id}`, with `PATCH` taking `{content, color, fontSize, fontFamily, lineHeight}`
+/// - `POST /notes/{id}/append` `{text}`, `POST /notes/{id}/focus`, `POST /notes/{id}/hide`
+fn route(app: &tauri::AppHandle, request: &HttpRequest) -> Result<HttpResponse, HttpResponse> {
+    let segments: Vec<String> = request.path
+        .trim_matches('/')
+        .split('/')
+        .map(|segment| urlencoding::decode(segment).map(|s| s.into_owned()).unwrap_or_else(|_| segment.to_string()))
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match segments.as_slice() {
+        ["notes"] => match request.method.as_str() {
+            "GET" => Ok(respond(
+                block_on(list_notes(app.clone())).and_then(|notes| serde_json::to_value(notes).map_err(|e| e.to_string())),
+            )),
+            "POST" => {
+                let body: NoteBody = parse_body(&request.body)?;
+                let color = parse_color(body.color)?;
+                let mut response = respond(block_on(create_note(app.clone(), body.content, color)).map(|id| json!({ "id": id })));
+                if response.status == 200 {
+                    response.status = 201;
+                }
+                Ok(response)
+            }
+            _ => Err(HttpResponse::error(405, "Method not allowed")),
+        },
+        ["notes", id] => handle_note_request(app, request, id, None),
+        ["notes", id, action] => handle_note_request(app, request, id, Some(action)),
+        _ => Err(HttpResponse::error(404, "Not found")),
+    }
+}
+
+fn handle_connection(app: &tauri::AppHandle, token: &str, mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let response = match read_request(&mut reader) {
+        Ok(request)
+            if !request
+                .authorization
+                .as_deref()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .is_some_and(|given| tokens_match(given, token)) =>
+        {
+            HttpResponse::error(401, "Missing or invalid token")
+        }
+        Ok(request) => {
+            println!("API request: {} {}", request.method, request.path);
+            route(app, &request).unwrap_or_else(|error| error)
+        }
+        Err(error) => error,
+    };
+
+    write_response(&mut stream, &response)
+}
+
+/// Starts serving the local API on 127.0.0.1, replacing a server that is already running
+pub fn start_api_server(app: &tauri::AppHandle, config: &ApiConfig) -> Result<(), String> {
+    stop_api_server();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, config.port))
+        .map_err(|e| format!("Failed to start local API on port {}: {}", config.port, e))?;
+    let stop = Arc::new(AtomicBool::new(false));
+    *SERVER.lock().unwrap() = Some(RunningServer { port: config.port, stop: stop.clone() });
+    println!("Local API listening on http://127.0.0.1:{}", config.port);
+
+    let app = app.clone();
+    let token = config.token.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            match stream {
+                Ok(stream) => {
+                    let app = app.clone();
+                    let token = token.clone();
+                    thread::spawn(move || {
+                        if let Err(e) = handle_connection(&app, &token, stream) {
+                            eprintln!("Failed to handle API request: {}", e);
+                        }
+                    });
+                }
+                Err(e) => eprintln!("Failed to accept API connection: {}", e),
+            }
+        }
+        println!("Local API stopped");
+    });
+
+    Ok(())
+}
+
+/// Stops the local API server, if it is running
+pub fn stop_api_server() {
+    if let Some(server) = SERVER.lock().unwrap().take() {
+        server.stop.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the stop flag and drops the listener
+        let _ = TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, server.port)), REQUEST_TIMEOUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn request_is_read_with_its_body() {
+        let raw = "POST /notes HTTP/1.1\r\nAuthorization: Bearer abc\r\nContent-Length: 2\r\n\r\n{}";
+        let request = read_request(&mut Cursor::new(raw)).unwrap_or_else(|response| panic!("{}", response.body));
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/notes"));
+        assert_eq!(request.authorization.as_deref(), Some("Bearer abc"));
+        assert_eq!(request.body, b"{}");
+    }
+
+    #[test]
+    fn oversized_request_line_is_rejected() {
+        let raw = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEADER_BYTES));
+        assert_eq!(read_request(&mut Cursor::new(raw)).err().map(|response| response.status), Some(431));
+    }
+
+    #[test]
+    fn oversized_headers_are_rejected() {
+        let header = format!("X-Padding: {}\r\n", "a".repeat(1000));
+        let raw = format!("GET /notes HTTP/1.1\r\n{}\r\n", header.repeat(20));
+        assert_eq!(read_request(&mut Cursor::new(raw)).err().map(|response| response.status), Some(431));
+    }
+
+    #[test]
+    fn tokens_must_match_exactly() {
+        assert!(tokens_match("0123abcd", "0123abcd"));
+        assert!(!tokens_match("0123abce", "0123abcd"));
+        assert!(!tokens_match("0123abc", "0123abcd"));
+        assert!(!tokens_match("", "0123abcd"));
+    }
+}
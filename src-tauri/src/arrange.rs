@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+/// How close, in logical pixels, a dragged note's edge has to be to snap
+pub const SNAP_DISTANCE: f64 = 12.0;
+/// Space between tiled notes and around the grid; larger than `SNAP_DISTANCE`
+/// so tiled notes don't snap together when dragged a little
+pub const TILE_GAP: f64 = 16.0;
+/// Offset between cascaded notes
+pub const CASCADE_STEP: f64 = 28.0;
+/// Notes are never tiled or fitted smaller than this
+pub const MIN_NOTE_WIDTH: f64 = 160.0;
+pub const MIN_NOTE_HEIGHT: f64 = 100.0;
+
+/// A window or screen area in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Rect { x, y, width, height }
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn center_x(&self) -> f64 {
+        self.x + self.width / 2.0
+    }
+
+    pub fn center_y(&self) -> f64 {
+        self.y + self.height / 2.0
+    }
+
+    /// Moves and shrinks the rectangle as little as possible to lie within `area`
+    pub fn fit_within(&self, area: &Rect) -> Rect {
+        let width = self.width.min(area.width);
+        let height = self.height.min(area.height);
+        Rect {
+            x: self.x.clamp(area.x, area.right() - width),
+            y: self.y.clamp(area.y, area.bottom() - height),
+            width,
+            height,
+        }
+    }
+}
+
+/// Which edge or center line of the reference note the others are lined up with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Alignment {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Same horizontal center, i.e. stacked in a column
+    CenterX,
+    /// Same vertical center, i.e. lined up in a row
+    CenterY,
+}
+
+impl Alignment {
+    pub const ALL: [Alignment; 6] = [
+        Alignment::Left,
+        Alignment::Right,
+        Alignment::Top,
+        Alignment::Bottom,
+        Alignment::CenterX,
+        Alignment::CenterY,
+    ];
+
+    /// Menu id of the Arrange menu item
+    pub fn menu_id(self) -> &'static str {
+        match self {
+            Alignment::Left => "align_left",
+            Alignment::Right => "align_right",
+            Alignment::Top => "align_top",
+            Alignment::Bottom => "align_bottom",
+            Alignment::CenterX => "align_center_x",
+            Alignment::CenterY => "align_center_y",
+        }
+    }
+}
+
+/// Whether two spans are closer than `distance`, or overlap
+fn spans_near(start_a: f64, end_a: f64, start_b: f64, end_b: f64, distance: f64) -> bool {
+    start_a < end_b + distance && start_b < end_a + distance
+}
+
+/// The smallest offset within `distance` that puts one of the `edges` onto one of the `targets`
+fn closest_snap(edges: &[f64], targets: &[f64], distance: f64) -> Option<f64> {
+    edges
+        .iter()
+        .flat_map(|edge| targets.iter().map(move |target| target - edge))
+        .filter(|offset| offset.abs() <= distance)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+}
+
+/// Moves a note so its edges that are within `distance` of the area's edges, or of a nearby note's
+/// edges, line up with them. Each axis snaps independently; the size never changes.
+pub fn snap_rect(rect: &Rect, others: &[Rect], area: &Rect, distance: f64) -> Rect {
+    let mut x_targets = vec![area.x, area.right()];
+    let mut y_targets = vec![area.y, area.bottom()];
+    for other in others {
+        // Only notes beside it (vertically overlapping) are snap targets for the left and right edges
+        if spans_near(rect.y, rect.bottom(), other.y, other.bottom(), distance) {
+            x_targets.extend([other.x, other.right()]);
+        }
+        if spans_near(rect.x, rect.right(), other.x, other.right(), distance) {
+            y_targets.extend([other.y, other.bottom()]);
+        }
+    }
+
+    let dx = closest_snap(&[rect.x, rect.right()], &x_targets, distance).unwrap_or(0.0);
+    let dy = closest_snap(&[rect.y, rect.bottom()], &y_targets, distance).unwrap_or(0.0);
+    Rect { x: rect.x + dx, y: rect.y + dy, ..*rect }
+}
+
+/// Lines notes up with an edge or center of `reference`, moving each along one axis only
+pub fn align_rects(rects: &[Rect], alignment: Alignment, reference: &Rect) -> Vec<Rect> {
+    rects
+        .iter()
+        .map(|rect| match alignment {
+            Alignment::Left => Rect { x: reference.x, ..*rect },
+            Alignment::Right => Rect { x: reference.right() - rect.width, ..*rect },
+            Alignment::Top => Rect { y: reference.y, ..*rect },
+            Alignment::Bottom => Rect { y: reference.bottom() - rect.height, ..*rect },
+            Alignment::CenterX => Rect { x: reference.center_x() - rect.width / 2.0, ..*rect },
+            Alignment::CenterY => Rect { y: reference.center_y() - rect.height / 2.0, ..*rect },
+        })
+        .collect()
+}
+
+/// Splits `area` into a grid of `count` equal cells separated by `gap`.
+/// Columns and rows are chosen so cells come out roughly 4:3, like a new note, but never so many
+/// that a cell would be smaller than the minimum note size. When there are more notes than cells,
+/// the extra notes go on top of the first ones, each layer offset by `gap`, and all stay within `area`.
+pub fn tile_rects(count: usize, area: &Rect, gap: f64) -> Vec<Rect> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let max_columns = (((area.width - gap) / (MIN_NOTE_WIDTH + gap)).floor() as usize).max(1);
+    let max_rows = (((area.height - gap) / (MIN_NOTE_HEIGHT + gap)).floor() as usize).max(1);
+
+    let aspect = (area.width / area.height.max(1.0)) / (4.0 / 3.0);
+    let columns = ((count as f64 * aspect).sqrt().ceil() as usize).clamp(1, count).min(max_columns);
+    let rows = count.div_ceil(columns).min(max_rows);
+    let cells = columns * rows;
+
+    let width = ((area.width - gap * (columns as f64 + 1.0)) / columns as f64).max(MIN_NOTE_WIDTH);
+    let height = ((area.height - gap * (rows as f64 + 1.0)) / rows as f64).max(MIN_NOTE_HEIGHT);
+
+    (0..count)
+        .map(|index| {
+            let cell = index % cells;
+            let layer = (index / cells) as f64;
+            let column = (cell % columns) as f64;
+            let row = (cell / columns) as f64;
+            Rect::new(
+                area.x + gap + column * (width + gap) + layer * gap,
+                area.y + gap + row * (height + gap) + layer * gap,
+                width,
+                height,
+            )
+            .fit_within(area)
+        })
+        .collect()
+}
+
+/// Stacks notes diagonally from the area's top-left corner, keeping their sizes (shrunk to fit).
+/// When the stack would leave the area it starts again at the top, one step further right.
+pub fn cascade_rects(sizes: &[(f64, f64)], area: &Rect, step: f64) -> Vec<Rect> {
+    let mut rects = Vec::with_capacity(sizes.len());
+    let mut run_x = area.x + step;
+    let mut offset = 0.0;
+
+    for &(width, height) in sizes {
+        let width = width.min(area.width - 2.0 * step).max(MIN_NOTE_WIDTH);
+        let height = height.min(area.height - 2.0 * step).max(MIN_NOTE_HEIGHT);
+
+        if offset > 0.0 && area.y + step + offset + height > area.bottom() {
+            run_x += step;
+            offset = 0.0;
+        }
+        let rect = Rect::new(run_x + offset, area.y + step + offset, width, height);
+        rects.push(rect.fit_within(area));
+        offset += step;
+    }
+
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect { x: 0.0, y: 25.0, width: 1440.0, height: 875.0 };
+
+    fn within(rect: &Rect, area: &Rect) -> bool {
+        rect.x >= area.x && rect.y >= area.y && rect.right() <= area.right() && rect.bottom() <= area.bottom()
+    }
+
+    fn overlap(a: &Rect, b: &Rect) -> bool {
+        a.x < b.right() && b.x < a.right() && a.y < b.bottom() && b.y < a.bottom()
+    }
+
+    #[test]
+    fn fit_within_moves_then_shrinks() {
+        let area = Rect::new(0.0, 0.0, 800.0, 600.0);
+        assert_eq!(Rect::new(700.0, -50.0, 400.0, 300.0).fit_within(&area), Rect::new(400.0, 0.0, 400.0, 300.0));
+        assert_eq!(Rect::new(100.0, 100.0, 1000.0, 700.0).fit_within(&area), area);
+        let inside = Rect::new(10.0, 20.0, 100.0, 100.0);
+        assert_eq!(inside.fit_within(&area), inside);
+    }
+
+    #[test]
+    fn snaps_to_area_edges_and_nearby_notes() {
+        let note = Rect::new(8.0, 300.0, 400.0, 300.0);
+        assert_eq!(snap_rect(&note, &[], &AREA, SNAP_DISTANCE), Rect::new(0.0, 300.0, 400.0, 300.0));
+
+        // Right edge 10px from a note beside it snaps against its left edge
+        let other = Rect::new(500.0, 320.0, 300.0, 200.0);
+        let note = Rect::new(90.0, 300.0, 400.0, 300.0);
+        assert_eq!(snap_rect(&note, &[other], &AREA, SNAP_DISTANCE).right(), 500.0);
+
+        // A note far above or below is not a target for the left and right edges
+        let other = Rect::new(500.0, 800.0, 300.0, 100.0);
+        assert_eq!(snap_rect(&note, &[other], &AREA, SNAP_DISTANCE), note);
+    }
+
+    #[test]
+    fn tiles_without_overlap() {
+        let rects = tile_rects(5, &AREA, TILE_GAP);
+        assert_eq!(rects.len(), 5);
+        for (index, rect) in rects.iter().enumerate() {
+            assert!(within(rect, &AREA), "{:?} is outside the area", rect);
+            assert!(rects[index + 1..].iter().all(|other| !overlap(rect, other)));
+        }
+        assert!(tile_rects(0, &AREA, TILE_GAP).is_empty());
+    }
+
+    #[test]
+    fn tiles_many_notes_inside_the_area() {
+        // Far more notes than minimum-size cells fit
+        let rects = tile_rects(200, &AREA, TILE_GAP);
+        assert_eq!(rects.len(), 200);
+        for rect in &rects {
+            assert!(within(rect, &AREA), "{:?} is outside the area", rect);
+            assert!(rect.width >= MIN_NOTE_WIDTH && rect.height >= MIN_NOTE_HEIGHT);
+        }
+
+        // An area smaller than one note still gets a note that fits
+        let small = Rect::new(0.0, 0.0, 120.0, 80.0);
+        assert!(tile_rects(3, &small, TILE_GAP).iter().all(|rect| within(rect, &small)));
+    }
+
+    #[test]
+    fn cascades_within_the_area() {
+        let sizes = vec![(400.0, 300.0); 40];
+        let rects = cascade_rects(&sizes, &AREA, CASCADE_STEP);
+        assert_eq!(rects[0], Rect::new(CASCADE_STEP, AREA.y + CASCADE_STEP, 400.0, 300.0));
+        assert_eq!(rects[1].x - rects[0].x, CASCADE_STEP);
+        assert!(rects.iter().all(|rect| within(rect, &AREA)));
+
+        // Notes larger than the area are shrunk to fit
+        let rects = cascade_rects(&[(3000.0, 2000.0)], &AREA, CASCADE_STEP);
+        assert!(within(&rects[0], &AREA));
+    }
+}
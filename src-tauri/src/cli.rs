@@ -0,0 +1,227 @@
+use std::fs;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::{Deserialize, Serialize};
+use crate::commands::file::{append_text, append_to_note};
+use crate::commands::window::{find_note_data, list_notes, note_summary};
+use crate::instance::{self, InstanceRequest};
+use crate::models::StickerData;
+use crate::palette::resolve_note_color;
+use crate::state::{load_app_state, new_note_path, save_app_state};
+use crate::window_manager::{create_note, next_free_note_id};
+
+pub const USAGE: &str = "Usage:
+  peach-leaf [FILE.md ...] [--new-note] [--show-all]   Open the app
+  peach-leaf new [--color <color>] [--text <text|->]   Create a note and print its id
+  peach-leaf list [--json]                             List notes
+  peach-leaf show <id>                                 Print a note's markdown
+  peach-leaf append <id> <text|->                      Append text to a note
+
+A text of `-` is read from standard input. Colors are hex values (#FFE4E1) or palette names (pink).";
+
+// Set while a subcommand runs in this process, see `log!`
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Whether this process is running a subcommand rather than the app
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::Relaxed)
+}
+
+/// A command-line subcommand. Runs headless when the app is not running,
+/// otherwise it is handed to the running instance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum CliCommand {
+    Help,
+    New { color: Option<String>, text: Option<String> },
+    List { json: bool },
+    Show { id: String },
+    Append { id: String, text: String },
+}
+
+fn read_stdin() -> Result<String, String> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text).map_err(|e| format!("Failed to read standard input: {}", e))?;
+    Ok(text)
+}
+
+/// `-` stands for standard input
+fn text_argument(value: String) -> Result<String, String> {
+    if value == "-" {
+        read_stdin()
+    } else {
+        Ok(value)
+    }
+}
+
+/// Value of `--name value` or `--name=value`
+fn option_value(arg: &str, name: &str, rest: &mut impl Iterator<Item = String>) -> Result<Option<String>, String> {
+    if arg == name {
+        return rest.next().map(Some).ok_or_else(|| format!("Missing value for {}", name));
+    }
+    Ok(arg.strip_prefix(name).and_then(|tail| tail.strip_prefix('=')).map(|value| value.to_string()))
+}
+
+fn positional(args: &mut impl Iterator<Item = String>, name: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("Missing <{}>", name))
+}
+
+fn no_more_args(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    match args.next() {
+        Some(arg) => Err(format!("Unexpected argument: {}", arg)),
+        None => Ok(()),
+    }
+}
+
+/// Parses a subcommand. Returns Ok(None) when the arguments are app launch arguments instead.
+/// Standard input is read here, so the command can be handed to another process.
+pub fn parse_cli_args(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some(subcommand) = args.first() else {
+        return Ok(None);
+    };
+    let mut rest = args[1..].iter().cloned();
+
+    let command = match subcommand.as_str() {
+        "help" | "--help" | "-h" => CliCommand::Help,
+        "new" => {
+            let mut color = None;
+            let mut text = None;
+            while let Some(arg) = rest.next() {
+                if let Some(value) = option_value(&arg, "--color", &mut rest)? {
+                    color = Some(resolve_note_color(&value).ok_or_else(|| format!("Unknown color: {}", value))?);
+                } else if let Some(value) = option_value(&arg, "--text", &mut rest)? {
+                    text = Some(text_argument(value)?);
+                } else {
+                    return Err(format!("Unexpected argument: {}", arg));
+                }
+            }
+            CliCommand::New { color, text }
+        }
+        "list" => {
+            let mut json = false;
+            for arg in rest {
+                match arg.as_str() {
+                    "--json" => json = true,
+                    _ => return Err(format!("Unexpected argument: {}", arg)),
+                }
+            }
+            CliCommand::List { json }
+        }
+        "show" => {
+            let id = positional(&mut rest, "id")?;
+            no_more_args(rest)?;
+            CliCommand::Show { id }
+        }
+        "append" => {
+            let id = positional(&mut rest, "id")?;
+            let text = text_argument(positional(&mut rest, "text")?)?;
+            no_more_args(rest)?;
+            CliCommand::Append { id, text }
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(command))
+}
+
+/// Runs a command and returns what it prints. With an app handle, open windows are updated;
+/// without one, the data directory is changed directly and the app picks it up on its next start.
+pub fn execute(command: CliCommand, app: Option<&tauri::AppHandle>) -> Result<String, String> {
+    match command {
+        CliCommand::Help => Ok(USAGE.to_string()),
+        CliCommand::New { color, text } => {
+            let text = text.unwrap_or_default();
+            let note_id = match app {
+                Some(app) => create_note(app, &text, color)?,
+                None => {
+                    let mut state = load_app_state()?;
+                    let note_id = next_free_note_id(|id| state.windows.iter().any(|w| w.id == id));
+                    let file_path = new_note_path(&note_id)?;
+                    fs::write(&file_path, &text).map_err(|e| format!("Failed to create note: {}", e))?;
+
+                    let mut sticker_data = StickerData::new_note(note_id.clone(), file_path.to_string_lossy().to_string());
+                    if let Some(color) = color {
+                        sticker_data.background_color = color;
+                    }
+                    state.windows.push(sticker_data);
+                    save_app_state(state)?;
+                    note_id
+                }
+            };
+            Ok(note_id)
+        }
+        CliCommand::List { json } => {
+            let summaries = match app {
+                Some(app) => tauri::async_runtime::block_on(list_notes(app.clone()))?,
+                None => load_app_state()?.windows.iter().map(note_summary).collect(),
+            };
+
+            if json {
+                serde_json::to_string_pretty(&summaries).map_err(|e| e.to_string())
+            } else {
+                Ok(summaries
+                    .iter()
+                    .map(|note| {
+                        format!(
+                            "{}\t{}{}",
+                            note.id,
+                            note.title.as_deref().unwrap_or("(empty)"),
+                            if note.hidden { "\t(hidden)" } else { "" }
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+        }
+        CliCommand::Show { id } => {
+            let note = find_note_data(&id).ok_or_else(|| format!("Note not found: {}", id))?;
+            fs::read_to_string(&note.file_path).map_err(|e| format!("Failed to read note: {}", e))
+        }
+        CliCommand::Append { id, text } => {
+            match app {
+                Some(app) => tauri::async_runtime::block_on(append_to_note(app.clone(), id, text))?,
+                None => {
+                    let note = find_note_data(&id).ok_or_else(|| format!("Note not found: {}", id))?;
+                    let content = fs::read_to_string(&note.file_path).unwrap_or_default();
+                    fs::write(&note.file_path, append_text(&content, &text))
+                        .map_err(|e| format!("Failed to write note: {}", e))?;
+                }
+            }
+            Ok(String::new())
+        }
+    }
+}
+
+/// Runs a subcommand: handed to the running instance if there is one, headless otherwise.
+/// Returns the process exit code.
+pub fn run_cli(command: CliCommand) -> i32 {
+    RUNNING.store(true, Ordering::Relaxed);
+    let result = match instance::acquire_headless() {
+        Ok(Some(_lock)) => execute(command, None),
+        Ok(None) => instance::forward_to_running_instance(InstanceRequest::Cli { command })
+            .and_then(|response| {
+                if response.ok {
+                    Ok(response.output.unwrap_or_default())
+                } else {
+                    Err(response.error.unwrap_or_else(|| "Command failed".to_string()))
+                }
+            }),
+        Err(e) => {
+            eprintln!("Failed to acquire instance lock, running without it: {}", e);
+            execute(command, None)
+        }
+    };
+
+    match result {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output.trim_end_matches('\n'));
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("peach-leaf: {}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use base64::{Engine as _, engine::general_purpose};
+
+/// An image pulled out of a non-bitmap clipboard format, ready to be stored with the note
+pub struct ExtractedImage {
+    pub bytes: Vec<u8>,
+    pub extension: String,
+}
+
+/// Returns the normalized extension if the path points to an image we can store
+pub fn image_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("png"),
+        "jpg" | "jpeg" => Some("jpg"),
+        "gif" => Some("gif"),
+        "webp" => Some("webp"),
+        "svg" => Some("svg"),
+        _ => None,
+    }
+}
+
+fn extension_for_mime(mime_type: &str) -> Option<&'static str> {
+    match mime_type.to_ascii_lowercase().as_str() {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "image/svg+xml" => Some("svg"),
+        _ => None,
+    }
+}
+
+fn read_image_file(path: &Path) -> Option<ExtractedImage> {
+    let extension = image_extension(path)?;
+    match fs::read(path) {
+        Ok(bytes) => Some(ExtractedImage { bytes, extension: extension.to_string() }),
+        Err(e) => {
+            eprintln!("Failed to read clipboard image file {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Converts a `file://` URL or plain absolute path into a filesystem path
+fn local_path_from_reference(reference: &str) -> Option<PathBuf> {
+    let reference = reference.trim();
+    let raw_path = if let Some(stripped) = reference.strip_prefix("file://") {
+        // file://localhost/path and file:///path both mean /path
+        let stripped = stripped.strip_prefix("localhost").unwrap_or(stripped);
+        urlencoding::decode(stripped).ok()?.into_owned()
+    } else {
+        reference.to_string()
+    };
+
+    let path = PathBuf::from(raw_path);
+    if path.is_absolute() && path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Decodes `data:image/...;base64,...` and `data:image/svg+xml,...` URLs
+fn decode_data_url(url: &str) -> Option<ExtractedImage> {
+    let rest = url.trim().strip_prefix("data:")?;
+    let (header, data) = rest.split_once(',')?;
+
+    let mut header_parts = header.split(';');
+    let extension = extension_for_mime(header_parts.next()?)?;
+    let is_base64 = header_parts.any(|part| part.eq_ignore_ascii_case("base64"));
+
+    let bytes = if is_base64 {
+        // Data URLs copied from HTML may be wrapped across lines
+        let compact: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+        general_purpose::STANDARD.decode(compact).ok()?
+    } else {
+        urlencoding::decode(data).ok()?.into_owned().into_bytes()
+    };
+
+    Some(ExtractedImage { bytes, extension: extension.to_string() })
+}
+
+/// Collects every image file from a clipboard file list, ignoring non-image files
+pub fn images_from_file_list(paths: &[PathBuf]) -> Vec<ExtractedImage> {
+    paths.iter().filter_map(|path| read_image_file(path)).collect()
+}
+
+/// Finds the `src` attribute values of every `<img>` tag in an HTML fragment
+fn extract_img_sources(html: &str) -> Vec<String> {
+    // ASCII lowercasing keeps byte offsets identical, so indices can be shared with `html`
+    let lower = html.to_ascii_lowercase();
+    let mut sources = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(tag_offset) = lower[search_from..].find("<img") {
+        let tag_start = search_from + tag_offset;
+        let tag_end = lower[tag_start..].find('>').map(|i| tag_start + i).unwrap_or(lower.len());
+        let tag = &lower[tag_start..tag_end];
+
+        if let Some(src_offset) = tag.find(" src=").or_else(|| tag.find("\nsrc=")) {
+            let value_start = tag_start + src_offset + " src=".len();
+            let value = &html[value_start..tag_end];
+            let src = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or(""),
+                _ => value.split(char::is_whitespace).next().unwrap_or(""),
+            };
+            if !src.is_empty() {
+                sources.push(src.replace("&amp;", "&"));
+            }
+        }
+
+        search_from = tag_end;
+    }
+
+    sources
+}
+
+/// Finds inline `<svg>...</svg>` elements in an HTML fragment
+fn extract_inline_svgs(html: &str) -> Vec<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut svgs = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(start_offset) = lower[search_from..].find("<svg") {
+        let start = search_from + start_offset;
+        let Some(end_offset) = lower[start..].find("</svg>") else {
+            break;
+        };
+        let end = start + end_offset + "</svg>".len();
+        svgs.push(html[start..end].to_string());
+        search_from = end;
+    }
+
+    svgs
+}
+
+fn svg_markup(svg: &str) -> String {
+    // Standalone SVG files need the namespace, which inline HTML SVG usually omits
+    if svg.contains("xmlns=") {
+        svg.to_string()
+    } else {
+        svg.replacen("<svg", "<svg xmlns=\"http://www.w3.org/2000/svg\"", 1)
+    }
+}
+
+/// Whether `lower` has an `<svg>` opening tag at `index`, not e.g. `<svgfoo`
+fn is_svg_opening(lower: &str, index: usize) -> bool {
+    lower[index..].strip_prefix("<svg").is_some_and(|after| {
+        matches!(after.chars().next(), Some('>' | '/' | ' ' | '\t' | '\n' | '\r'))
+    })
+}
+
+/// An SVG document copied as plain text, e.g. from an editor or a design tool: the whole text
+/// has to be one `<svg>...</svg>` element, optionally preceded by an `<?xml ...?>` declaration.
+/// Any other text is pasted as text.
+pub fn image_from_text(text: &str) -> Option<ExtractedImage> {
+    let mut svg = text.trim();
+    if svg.starts_with("<?xml") {
+        svg = svg[svg.find("?>")? + 2..].trim_start();
+    }
+
+    let lower = svg.to_ascii_lowercase();
+    if !is_svg_opening(&lower, 0) || !lower.ends_with("</svg>") {
+        return None;
+    }
+
+    // The first <svg> must only close at the very end, so "<svg/>text<svg/>" is not one document
+    let mut depth = 0usize;
+    let mut index = 0;
+    while let Some(offset) = lower[index..].find("<svg").into_iter().chain(lower[index..].find("</svg>")).min() {
+        let at = index + offset;
+        if lower[at..].starts_with("</svg>") {
+            depth = depth.checked_sub(1)?;
+            index = at + "</svg>".len();
+            if depth == 0 && index != lower.len() {
+                return None;
+            }
+        } else {
+            index = at + "<svg".len();
+            if is_svg_opening(&lower, at) {
+                let tag_end = at + lower[at..].find('>')?;
+                // A self-closing root would end the document right here
+                if lower[..tag_end].ends_with('/') && depth == 0 {
+                    return None;
+                }
+                if !lower[..tag_end].ends_with('/') {
+                    depth += 1;
+                }
+                index = tag_end + 1;
+            }
+        }
+    }
+    if depth != 0 {
+        return None;
+    }
+
+    Some(ExtractedImage { bytes: svg_markup(svg).into_bytes(), extension: "svg".to_string() })
+}
+
+/// Whether an HTML fragment shows any text, outside tags and inline SVG, styles and scripts
+fn html_has_text(html: &str) -> bool {
+    let lower = html.to_ascii_lowercase();
+    let mut index = 0;
+
+    while index < html.len() {
+        let rest = &lower[index..];
+        if rest.starts_with('<') {
+            // Skip whole elements whose content is not shown as text
+            let skipped = ["<svg", "<style", "<script"].iter().find_map(|opening| {
+                let after = rest.strip_prefix(opening)?.chars().next();
+                if !matches!(after, Some('>' | '/' | ' ' | '\t' | '\n' | '\r')) {
+                    return None;
+                }
+                let closing = format!("</{}>", &opening[1..]);
+                Some(rest.find(&closing).map(|end| end + closing.len()).unwrap_or(rest.len()))
+            });
+            index += skipped.unwrap_or_else(|| rest.find('>').map(|end| end + 1).unwrap_or(rest.len()));
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = html[index..index + end].replace("&nbsp;", " ").replace("&#160;", " ");
+        if text.chars().any(|c| !c.is_whitespace()) {
+            return true;
+        }
+        index += end;
+    }
+
+    false
+}
+
+/// Extracts images from an HTML fragment that is only images: `<img>` tags with data URLs or local
+/// files, and inline SVG. A fragment with text is pasted as text, so icons in it are not images.
+pub fn images_from_html(html: &str) -> Vec<ExtractedImage> {
+    if html_has_text(html) {
+        return Vec::new();
+    }
+
+    let mut images = Vec::new();
+
+    for src in extract_img_sources(html) {
+        if src.starts_with("data:") {
+            match decode_data_url(&src) {
+                Some(image) => images.push(image),
+                None => println!("Skipping unsupported data URL in clipboard HTML"),
+            }
+        } else if let Some(image) = local_path_from_reference(&src).and_then(|path| read_image_file(&path)) {
+            images.push(image);
+        } else {
+            println!("Skipping remote or missing image in clipboard HTML: {}", src);
+        }
+    }
+
+    for svg in extract_inline_svgs(html) {
+        images.push(ExtractedImage {
+            bytes: svg_markup(&svg).into_bytes(),
+            extension: "svg".to_string(),
+        });
+    }
+
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIXEL: &str = "data:image/png;base64,iVBORw0KGgo=";
+
+    #[test]
+    fn image_only_html_gives_its_images() {
+        let html = format!("<meta charset=\"utf-8\"><img src=\"{}\" alt=\"chart\">", PIXEL);
+        let images = images_from_html(&html);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].extension, "png");
+
+        let svg = images_from_html("<div> <svg viewBox=\"0 0 1 1\"><text>label</text></svg>&nbsp;</div>");
+        assert_eq!(svg.len(), 1);
+        assert!(String::from_utf8_lossy(&svg[0].bytes).contains("xmlns="));
+    }
+
+    #[test]
+    fn rich_text_with_icons_is_not_an_image() {
+        let html = format!(
+            "<p><svg class=\"icon\"><path d=\"M0 0\"/></svg> Release notes <img src=\"{}\"></p>",
+            PIXEL
+        );
+        assert!(images_from_html(&html).is_empty());
+        assert!(images_from_html("<style>p { color: red }</style><p>Hello</p>").is_empty());
+    }
+
+    #[test]
+    fn svg_documents_copied_as_text_are_images() {
+        let svg = "<svg viewBox=\"0 0 10 10\"><svg x=\"1\"><rect/></svg></svg>";
+        let image = image_from_text(&format!("\n  {}\n", svg)).unwrap();
+        assert_eq!(image.extension, "svg");
+        assert!(String::from_utf8_lossy(&image.bytes).starts_with("<svg xmlns="));
+
+        let declared = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>";
+        assert_eq!(image_from_text(declared).unwrap().bytes, declared.split_once('\n').unwrap().1.as_bytes());
+    }
+
+    #[test]
+    fn other_text_is_not_sniffed() {
+        assert!(image_from_text("Use <svg> for icons: <svg></svg>").is_none());
+        assert!(image_from_text("<svg></svg> and <svg></svg>").is_none());
+        assert!(image_from_text("<svg/>text</svg>").is_none());
+        assert!(image_from_text("<svgfoo></svg>").is_none());
+        assert!(image_from_text("<?xml version=\"1.0\"?><note/>").is_none());
+        assert!(image_from_text("/Users/me/logo.svg").is_none());
+    }
+
+    #[test]
+    fn styles_and_scripts_are_not_text() {
+        assert!(!html_has_text("<style>img { width: 10px }</style><script>x()</script><img src=\"a.png\">"));
+        assert!(html_has_text("<b>안녕</b>"));
+    }
+}
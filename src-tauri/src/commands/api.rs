@@ -0,0 +1,30 @@
+use crate::api::{start_api_server, stop_api_server};
+use crate::instance::random_token;
+use crate::models::ApiConfig;
+use crate::state::{load_config, save_config};
+
+#[tauri::command]
+pub async fn get_api_config() -> Result<ApiConfig, String> {
+    Ok(load_config().api)
+}
+
+/// Turns the local API on or off. A token is generated the first time it is enabled.
+/// Returns the API settings, including the port and token clients need.
+#[tauri::command]
+pub async fn set_api_enabled(app: tauri::AppHandle, enabled: bool) -> Result<ApiConfig, String> {
+    println!("set_api_enabled called: {}", enabled);
+
+    let mut config = load_config();
+    if enabled {
+        if config.api.token.is_empty() {
+            config.api.token = random_token();
+        }
+        start_api_server(&app, &config.api)?;
+    } else {
+        stop_api_server();
+    }
+
+    config.api.enabled = enabled;
+    save_config(&config)?;
+    Ok(config.api)
+}
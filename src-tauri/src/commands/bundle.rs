@@ -0,0 +1,288 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri_plugin_dialog::DialogExt;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+use crate::commands::export::sanitize_file_name;
+use crate::commands::import::unique_image_name;
+use crate::commands::window::find_note_data;
+use crate::fonts::{clamp_font_size, clamp_line_height, normalize_font_family};
+use crate::markdown::{note_title, rewrite_image_sources};
+use crate::models::{NoteBundleManifest, StickerData};
+use crate::state::{new_note_path, note_images_dir};
+use crate::window_manager::{generate_note_id, open_note_window};
+
+const BUNDLE_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+const NOTE_NAME: &str = "note.md";
+const IMAGES_DIR: &str = "images";
+
+/// Creation time of a note: the millis encoded in `note-{millis}` ids, or the file's creation time
+fn note_created_at(note: &StickerData) -> Option<u64> {
+    if let Some(millis) = note.id.strip_prefix("note-").and_then(|m| m.parse::<u64>().ok()) {
+        return Some(millis);
+    }
+
+    fs::metadata(&note.file_path)
+        .and_then(|metadata| metadata.created())
+        .ok()
+        .and_then(|created| created.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+}
+
+/// Name of the image a link points to, if it is a file directly inside the note's images folder
+fn linked_image_name<'a>(decoded_dest: &'a str, link_prefix: &str) -> Option<&'a str> {
+    let dest = decoded_dest.strip_prefix("./").unwrap_or(decoded_dest);
+    let prefix = link_prefix.strip_prefix("./").unwrap_or(link_prefix);
+    let name = dest.strip_prefix(prefix)?.strip_prefix('/')?;
+    (!name.is_empty() && !name.contains('/')).then_some(name)
+}
+
+/// Writes a note, its images and a manifest into a zip archive
+pub fn write_note_bundle(note: &StickerData, destination: &Path) -> Result<(), String> {
+    let note_path = Path::new(&note.file_path);
+    let markdown = fs::read_to_string(note_path)
+        .map_err(|e| format!("Failed to read note: {}", e))?;
+
+    let (images_dir, link_prefix) = note_images_dir(note_path)?;
+
+    let manifest = NoteBundleManifest {
+        version: BUNDLE_VERSION,
+        title: note_title(&markdown).unwrap_or_else(|| note.id.clone()),
+        background_color: note.background_color.clone(),
+        text_color: note.text_color.clone(),
+        font_size: note.font_size,
+        font_family: note.font_family.clone(),
+        line_height: note.line_height,
+        width: Some(note.width),
+        height: Some(note.height),
+        created_at: note_created_at(note),
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+
+    let file = fs::File::create(destination)
+        .map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    zip.start_file(MANIFEST_NAME, options).map_err(|e| e.to_string())?;
+    zip.write_all(manifest_json.as_bytes()).map_err(|e| e.to_string())?;
+
+    // Only the images the note links to: a vault's attachments folder is shared with other notes
+    let mut image_names = Vec::new();
+    let portable_markdown = rewrite_image_sources(&markdown, |dest| {
+        let decoded = urlencoding::decode(dest).map(|d| d.into_owned()).unwrap_or_else(|_| dest.to_string());
+        let name = linked_image_name(&decoded, &link_prefix)?;
+        if !images_dir.join(name).is_file() {
+            return None;
+        }
+        if !image_names.iter().any(|existing| existing == name) {
+            image_names.push(name.to_string());
+        }
+        Some(format!("./{}/{}", IMAGES_DIR, urlencoding::encode(name)))
+    });
+
+    zip.start_file(NOTE_NAME, options).map_err(|e| e.to_string())?;
+    zip.write_all(portable_markdown.as_bytes()).map_err(|e| e.to_string())?;
+
+    for file_name in &image_names {
+        let path = images_dir.join(file_name);
+        let bytes = fs::read(&path)
+            .map_err(|e| format!("Failed to read image {:?}: {}", path, e))?;
+        zip.start_file(format!("{}/{}", IMAGES_DIR, file_name), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+
+    zip.finish().map_err(|e| format!("Failed to finish bundle: {}", e))?;
+    Ok(())
+}
+
+/// Unpacks a bundle as a new note in the notes directory, with its images in the note's images folder.
+/// Returns the manifest (if the bundle has one) and the path of the written note.
+pub fn unpack_note_bundle(bundle_path: &Path, note_id: &str) -> Result<(Option<NoteBundleManifest>, PathBuf), String> {
+    let file = fs::File::open(bundle_path)
+        .map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| format!("Invalid note bundle: {}", e))?;
+
+    // Check before extracting anything, so a bundle without a note leaves no images behind
+    let has_note = (0..archive.len()).any(|index| {
+        archive.by_index(index).ok().and_then(|entry| entry.enclosed_name()).as_deref() == Some(Path::new(NOTE_NAME))
+    });
+    if !has_note {
+        return Err(format!("Bundle has no {}", NOTE_NAME));
+    }
+
+    let note_path = new_note_path(note_id)?;
+    let (images_dir, link_prefix) = note_images_dir(&note_path)?;
+
+    let mut image_names = HashMap::new();
+    let result = extract_bundle_entries(&mut archive, &images_dir, &mut image_names);
+    if result.is_err() {
+        // Only this bundle's images: a vault's attachments folder is shared with other notes
+        for name in image_names.values() {
+            let _ = fs::remove_file(images_dir.join(name));
+        }
+        let _ = fs::remove_dir(&images_dir);
+    }
+    let (manifest, markdown) = result?;
+
+    let markdown = rewrite_image_sources(&markdown, |dest| {
+        let decoded = urlencoding::decode(dest).map(|d| d.into_owned()).unwrap_or_else(|_| dest.to_string());
+        let name = decoded.strip_prefix(&format!("./{}/", IMAGES_DIR))?;
+        image_names.get(name).map(|new_name| format!("{}/{}", link_prefix, new_name))
+    });
+    fs::write(&note_path, markdown)
+        .map_err(|e| format!("Failed to write note: {}", e))?;
+
+    Ok((manifest, note_path))
+}
+
+/// Reads the manifest and note of a bundle and writes its images into `images_dir`.
+/// `image_names` maps each written image's name in the bundle to its name on disk,
+/// which differs when the folder already has a file of that name.
+fn extract_bundle_entries(
+    archive: &mut ZipArchive<fs::File>,
+    images_dir: &Path,
+    image_names: &mut HashMap<String, String>,
+) -> Result<(Option<NoteBundleManifest>, String), String> {
+    let mut manifest = None;
+    let mut markdown = None;
+    let mut used_names = HashSet::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Reject entries that would escape the archive root
+        let Some(entry_path) = entry.enclosed_name() else {
+            println!("Skipping unsafe bundle entry: {}", entry.name());
+            continue;
+        };
+
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+
+        if entry_path == Path::new(MANIFEST_NAME) {
+            manifest = Some(serde_json::from_slice::<NoteBundleManifest>(&bytes)
+                .map_err(|e| format!("Invalid bundle manifest: {}", e))?);
+        } else if entry_path == Path::new(NOTE_NAME) {
+            markdown = Some(String::from_utf8_lossy(&bytes).to_string());
+        } else if entry_path.parent() == Some(Path::new(IMAGES_DIR)) {
+            if let Some(file_name) = entry_path.file_name().and_then(|name| name.to_str()) {
+                fs::create_dir_all(images_dir)
+                    .map_err(|e| format!("Failed to create images directory: {}", e))?;
+                let new_name = unique_image_name(file_name, images_dir, &mut used_names);
+                fs::write(images_dir.join(&new_name), bytes)
+                    .map_err(|e| format!("Failed to write image: {}", e))?;
+                image_names.insert(file_name.to_string(), new_name);
+            }
+        } else {
+            println!("Ignoring unknown bundle entry: {:?}", entry_path);
+        }
+    }
+
+    let markdown = markdown.ok_or_else(|| format!("Bundle has no {}", NOTE_NAME))?;
+    Ok((manifest, markdown))
+}
+
+/// Export a note with its images and a metadata manifest as a single `.peachleaf` (zip) file.
+/// Returns the saved path, or None if the user cancelled.
+#[tauri::command]
+pub async fn export_note_bundle(app: tauri::AppHandle, note_id: String) -> Result<Option<String>, String> {
+    println!("export_note_bundle called: {}", note_id);
+
+    let note = find_note_data(&note_id)
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let markdown = fs::read_to_string(&note.file_path).unwrap_or_default();
+    let default_name = format!(
+        "{}.peachleaf",
+        sanitize_file_name(&note_title(&markdown).unwrap_or_else(|| note.id.clone()))
+    );
+
+    let destination = app.dialog()
+        .file()
+        .set_title("Export Note Bundle")
+        .set_file_name(default_name)
+        .add_filter("PeachLeaf Note", &["peachleaf"])
+        .add_filter("Zip Archive", &["zip"])
+        .blocking_save_file();
+
+    let Some(destination) = destination else {
+        println!("Bundle export cancelled");
+        return Ok(None);
+    };
+    let destination = destination.into_path()
+        .map_err(|e| format!("Invalid export path: {}", e))?;
+
+    write_note_bundle(&note, &destination)?;
+
+    println!("Note {} exported as bundle to {:?}", note_id, destination);
+    Ok(Some(destination.to_string_lossy().to_string()))
+}
+
+/// Import a `.peachleaf`/`.zip` note bundle as a new note and open it in a window.
+/// If no path is given, a file dialog is shown. Returns the new note id, or None if cancelled.
+#[tauri::command]
+pub async fn import_note_bundle(app: tauri::AppHandle, bundle_path: Option<String>) -> Result<Option<String>, String> {
+    println!("import_note_bundle called: {:?}", bundle_path);
+
+    let bundle_path = match bundle_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app.dialog()
+                .file()
+                .set_title("Import Note Bundle")
+                .add_filter("PeachLeaf Note", &["peachleaf", "zip"])
+                .blocking_pick_file();
+            match picked {
+                Some(path) => path.into_path().map_err(|e| format!("Invalid bundle path: {}", e))?,
+                None => {
+                    println!("Bundle import cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    let note_id = generate_note_id(&app);
+    let (manifest, note_path) = unpack_note_bundle(&bundle_path, &note_id)?;
+
+    let mut sticker_data = StickerData::new_note(note_id.clone(), note_path.to_string_lossy().to_string());
+    sticker_data.mode = "preview".to_string();
+    if let Some(manifest) = manifest {
+        sticker_data.width = manifest.width.unwrap_or(sticker_data.width);
+        sticker_data.height = manifest.height.unwrap_or(sticker_data.height);
+        sticker_data.background_color = manifest.background_color;
+        sticker_data.text_color = manifest.text_color;
+        sticker_data.font_size = clamp_font_size(manifest.font_size as i64);
+        // Bundles come from elsewhere, so their font goes through the same checks as the menu's
+        sticker_data.font_family = normalize_font_family(&manifest.font_family).unwrap_or(sticker_data.font_family);
+        sticker_data.line_height = clamp_line_height(manifest.line_height);
+    }
+
+    open_note_window(&app, sticker_data);
+
+    println!("Imported bundle {:?} as note {}", bundle_path, note_id);
+    Ok(Some(note_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_links_into_the_images_folder_are_bundled() {
+        assert_eq!(linked_image_name("./note_images/a.png", "./note_images"), Some("a.png"));
+        assert_eq!(linked_image_name("note_images/a b.png", "./note_images"), Some("a b.png"));
+        assert_eq!(linked_image_name("../attachments/a.png", "../attachments"), Some("a.png"));
+        assert_eq!(linked_image_name("./note_images/sub/a.png", "./note_images"), None);
+        assert_eq!(linked_image_name("./note_images_old/a.png", "./note_images"), None);
+        assert_eq!(linked_image_name("https://example.com/a.png", "./note_images"), None);
+    }
+}
@@ -0,0 +1,40 @@
+use std::fs;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use crate::commands::window::find_note_data;
+use crate::markdown::render_note_fragment;
+
+/// Copy a note to the clipboard as rendered HTML (images inlined) with the raw markdown as plain text.
+/// Rich editors pick up the HTML; plain text targets get the markdown.
+#[tauri::command]
+pub async fn copy_note_to_clipboard(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+    println!("copy_note_to_clipboard called: {}", note_id);
+
+    let note = find_note_data(&note_id)
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let markdown = fs::read_to_string(&note.file_path)
+        .map_err(|e| format!("Failed to read note: {}", e))?;
+
+    let html = render_note_fragment(&markdown, &note);
+
+    app.clipboard()
+        .write_html(html, Some(markdown))
+        .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+
+    println!("Note {} copied to clipboard as HTML and markdown", note_id);
+    Ok(())
+}
+
+/// Render a note as the HTML fragment `copy_note_to_clipboard` writes (images inlined as data URLs).
+/// Edit → Copy Note As... rasterizes this in the webview and writes the PNG, this HTML and the
+/// markdown as one clipboard item, as arboard can only hold one format besides HTML's plain text.
+#[tauri::command]
+pub async fn render_note_for_clipboard(note_id: String) -> Result<String, String> {
+    println!("render_note_for_clipboard called: {}", note_id);
+
+    let note = find_note_data(&note_id)
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let markdown = fs::read_to_string(&note.file_path)
+        .map_err(|e| format!("Failed to read note: {}", e))?;
+
+    Ok(render_note_fragment(&markdown, &note))
+}
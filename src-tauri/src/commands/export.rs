@@ -0,0 +1,60 @@
+use std::fs;
+use tauri_plugin_dialog::DialogExt;
+use crate::commands::window::find_note_data;
+use crate::markdown::{note_title, render_note_document};
+
+/// Turn a note title into something safe to use as a file name
+pub fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    let trimmed = sanitized.trim().trim_matches('.');
+    if trimmed.is_empty() {
+        "note".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Export a note to a standalone HTML file chosen with a save dialog.
+/// Returns the saved path, or None if the user cancelled.
+#[tauri::command]
+pub async fn export_note(app: tauri::AppHandle, note_id: String) -> Result<Option<String>, String> {
+    println!("export_note called: {}", note_id);
+
+    let note = find_note_data(&note_id)
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let markdown = fs::read_to_string(&note.file_path)
+        .map_err(|e| format!("Failed to read note: {}", e))?;
+
+    let default_name = format!(
+        "{}.html",
+        sanitize_file_name(&note_title(&markdown).unwrap_or_else(|| note.id.clone()))
+    );
+
+    let destination = app.dialog()
+        .file()
+        .set_title("Export Note as HTML")
+        .set_file_name(default_name)
+        .add_filter("HTML", &["html", "htm"])
+        .blocking_save_file();
+
+    let Some(destination) = destination else {
+        println!("Export cancelled");
+        return Ok(None);
+    };
+    let destination = destination.into_path()
+        .map_err(|e| format!("Invalid export path: {}", e))?;
+
+    let html = render_note_document(&markdown, &note);
+    fs::write(&destination, html)
+        .map_err(|e| format!("Failed to write export: {}", e))?;
+
+    println!("Note {} exported to {:?}", note_id, destination);
+    Ok(Some(destination.to_string_lossy().to_string()))
+}
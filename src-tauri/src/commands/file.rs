@@ -1,9 +1,18 @@
 use std::fs;
-use crate::state::get_notes_dir;
+use std::path::Path;
+use tauri::{Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogResult};
+use crate::commands::import::import_markdown_file;
+use crate::commands::window::{WINDOW_METADATA, cache_note_title, find_note_data, follow_moved_note, refresh_note_lists};
+use crate::models::StickerData;
+use crate::state::{ensure_notes_dir, get_notes_dir};
+use crate::vault::active_vault;
+use crate::window_manager::{generate_note_id, open_note_window};
 
 #[tauri::command]
-pub async fn read_file(file_path: String) -> Result<String, String> {
+pub async fn read_file(app: tauri::AppHandle, file_path: String) -> Result<String, String> {
     println!("read_file called: {}", file_path);
+    let file_path = follow_moved_note(&app, &file_path).unwrap_or(file_path);
     let result = fs::read_to_string(&file_path)
         .map_err(|e| e.to_string());
     match &result {
@@ -14,20 +23,208 @@ pub async fn read_file(file_path: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub async fn write_file(file_path: String, content: String) -> Result<(), String> {
-    fs::write(&file_path, content)
-        .map_err(|e| e.to_string())
+pub async fn write_file(app: tauri::AppHandle, file_path: String, content: String) -> Result<(), String> {
+    // Never recreate a vault note at the path it was renamed away from
+    let file_path = follow_moved_note(&app, &file_path).unwrap_or(file_path);
+    fs::write(&file_path, &content)
+        .map_err(|e| e.to_string())?;
+    if cache_note_title(&file_path, &content) {
+        refresh_note_lists(&app);
+    }
+    Ok(())
+}
+
+/// Adds text as new lines at the end of existing content
+pub fn append_text(content: &str, text: &str) -> String {
+    let mut appended = content.to_string();
+    if !appended.is_empty() && !appended.ends_with('\n') {
+        appended.push('\n');
+    }
+    appended.push_str(text);
+    if !appended.ends_with('\n') {
+        appended.push('\n');
+    }
+    appended
+}
+
+/// Tells an open note that its file changed outside its editor, so it reloads
+/// instead of overwriting the change on its next save
+pub fn notify_content_changed(app: &tauri::AppHandle, note_id: &str) {
+    if let Some(window) = app.get_webview_window(note_id) {
+        let _ = window.emit(&format!("content_changed_{}", note_id), ());
+    }
+}
+
+/// Replaces a note's markdown
+#[tauri::command]
+pub async fn set_note_content(app: tauri::AppHandle, note_id: String, content: String) -> Result<(), String> {
+    println!("set_note_content called: {} ({} bytes)", note_id, content.len());
+    let note = find_note_data(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+    write_file(app.clone(), note.file_path, content).await?;
+    notify_content_changed(&app, &note_id);
+    Ok(())
+}
+
+/// Appends text as new lines at the end of a note
+#[tauri::command]
+pub async fn append_to_note(app: tauri::AppHandle, note_id: String, text: String) -> Result<(), String> {
+    println!("append_to_note called: {} ({} bytes)", note_id, text.len());
+    let note = find_note_data(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let read = read_file(app.clone(), note.file_path.clone()).await;
+    // The note may have been followed to a new path while reading
+    let file_path = find_note_data(&note_id).map(|data| data.file_path).unwrap_or(note.file_path);
+    let content = match read {
+        Ok(content) => content,
+        Err(_) if !Path::new(&file_path).exists() => String::new(),
+        Err(e) => return Err(e),
+    };
+    write_file(app.clone(), file_path, append_text(&content, &text)).await?;
+    notify_content_changed(&app, &note_id);
+    Ok(())
+}
+
+/// Ask whether an opened markdown file should be copied into the notes directory or linked in place.
+/// Returns None if the user cancelled.
+fn ask_link_or_copy(app: &tauri::AppHandle, file_name: &str) -> Option<bool> {
+    const COPY_LABEL: &str = "Copy into Notes";
+    const LINK_LABEL: &str = "Link in Place";
+
+    let result = app.dialog()
+        .message(format!(
+            "Copy \"{}\" into PeachLeaf's notes folder, or edit the original file in place?",
+            file_name
+        ))
+        .title("Open Markdown File")
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            COPY_LABEL.to_string(),
+            LINK_LABEL.to_string(),
+            "Cancel".to_string(),
+        ))
+        .blocking_show_with_result();
+
+    // Platforms report custom buttons either by label or as Yes/No
+    match result {
+        MessageDialogResult::Yes => Some(false),
+        MessageDialogResult::No => Some(true),
+        MessageDialogResult::Custom(label) if label == COPY_LABEL => Some(false),
+        MessageDialogResult::Custom(label) if label == LINK_LABEL => Some(true),
+        _ => None,
+    }
 }
 
+/// Open an existing markdown file as a note via a file dialog.
+/// `mode` is "copy" or "link"; if omitted the user is asked.
+/// Returns the id of the note showing the file, or None if the user cancelled.
 #[tauri::command]
-pub async fn select_file(_app: tauri::AppHandle) -> Result<Option<String>, String> {
-    // For now, return None - file dialog will be added later
-    // In Tauri 2.x, file dialog is a separate plugin
-    Ok(None)
+pub async fn select_file(app: tauri::AppHandle, mode: Option<String>) -> Result<Option<String>, String> {
+    println!("select_file called: mode={:?}", mode);
+
+    let picked = app.dialog()
+        .file()
+        .set_title("Open Markdown File")
+        .add_filter("Markdown", &["md", "markdown"])
+        .blocking_pick_file();
+
+    let Some(picked) = picked else {
+        println!("File selection cancelled");
+        return Ok(None);
+    };
+    let source_path = picked.into_path()
+        .map_err(|e| format!("Invalid file path: {}", e))?;
+
+    open_markdown_file(&app, &source_path, mode.as_deref())
+}
+
+/// Open a markdown file as a note, copied into the notes directory or linked in place.
+/// `mode` is "copy" or "link"; if omitted the user is asked.
+/// Returns the id of the note showing the file, or None if the user cancelled.
+pub fn open_markdown_file(
+    app: &tauri::AppHandle,
+    source_path: &Path,
+    mode: Option<&str>,
+) -> Result<Option<String>, String> {
+    let source_path_str = source_path.to_string_lossy().to_string();
+
+    // A linked file that is already open just gets focused
+    let existing_note = WINDOW_METADATA.lock().unwrap()
+        .values()
+        .find(|data| data.file_path == source_path_str)
+        .map(|data| data.id.clone());
+    if let Some(note_id) = existing_note {
+        if let Some(window) = app.get_webview_window(&note_id) {
+            println!("File already open as note {}, focusing it", note_id);
+            let _ = window.set_focus();
+            return Ok(Some(note_id));
+        }
+    }
+
+    // Files that are already part of the vault are always edited in place
+    let in_vault = active_vault()
+        .map(|vault| source_path.starts_with(&vault.path))
+        .unwrap_or(false);
+
+    let link = match mode {
+        _ if in_vault => true,
+        Some("link") => true,
+        Some("copy") => false,
+        _ => {
+            let file_name = source_path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| source_path_str.clone());
+            match ask_link_or_copy(app, &file_name) {
+                Some(link) => link,
+                None => {
+                    println!("File open cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    let note_id = generate_note_id(app);
+    let sticker_data = if link {
+        let mut sticker_data = StickerData::new_note(note_id.clone(), source_path_str);
+        sticker_data.mode = "preview".to_string();
+        sticker_data
+    } else {
+        // Copies bring their relative images along, like a folder import
+        match import_markdown_file(source_path, &note_id)? {
+            Some(sticker_data) => sticker_data,
+            None => {
+                let destination = ensure_notes_dir()?.join(format!("{}.md", note_id));
+                fs::write(&destination, "")
+                    .map_err(|e| format!("Failed to create note: {}", e))?;
+                StickerData::new_note(note_id.clone(), destination.to_string_lossy().to_string())
+            }
+        }
+    };
+
+    println!("Opening {:?} as note {} ({})", source_path, note_id, if link { "linked" } else { "copied" });
+
+    open_note_window(app, sticker_data);
+
+    Ok(Some(note_id))
 }
 
 #[tauri::command]
 pub async fn delete_note_file(note_id: String) -> Result<(), String> {
+    // Vault notes belong to the vault: closing one only removes a file that was never written to
+    if active_vault().is_some() {
+        let file_path = WINDOW_METADATA.lock().unwrap().get(&note_id).map(|data| data.file_path.clone());
+        if let Some(file_path) = file_path {
+            let is_empty = fs::read_to_string(&file_path)
+                .map(|content| content.trim().is_empty())
+                .unwrap_or(false);
+            if is_empty {
+                println!("Deleting empty vault note: {}", file_path);
+                fs::remove_file(&file_path).map_err(|e| e.to_string())?;
+            } else {
+                println!("Keeping vault note file: {}", file_path);
+            }
+        }
+        return Ok(());
+    }
+
     let notes_dir = get_notes_dir();
     let file_path = notes_dir.join(format!("{}.md", note_id));
 
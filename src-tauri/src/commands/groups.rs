@@ -0,0 +1,59 @@
+use crate::groups::{self, GroupSummary};
+use crate::models::NoteGroup;
+
+/// Every group, with the ids of its open notes
+#[tauri::command]
+pub async fn list_groups() -> Result<Vec<GroupSummary>, String> {
+    Ok(groups::list_groups())
+}
+
+/// Creates a group with the given notes. Without a name it is named "Group N".
+#[tauri::command]
+pub async fn create_group(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    note_ids: Option<Vec<String>>,
+) -> Result<NoteGroup, String> {
+    println!("create_group called: {:?}", name);
+    groups::create_group(&app, name.as_deref(), &note_ids.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn rename_group(app: tauri::AppHandle, group_id: String, name: String) -> Result<NoteGroup, String> {
+    println!("rename_group called: {} -> {}", group_id, name);
+    groups::rename_group(&app, &group_id, &name)
+}
+
+/// Deletes a group, keeping its notes
+#[tauri::command]
+pub async fn delete_group(app: tauri::AppHandle, group_id: String) -> Result<Vec<GroupSummary>, String> {
+    println!("delete_group called: {}", group_id);
+    groups::delete_group(&app, &group_id)?;
+    Ok(groups::list_groups())
+}
+
+/// Moves a note into a group, or out of its group without a group id
+#[tauri::command]
+pub async fn set_note_group(app: tauri::AppHandle, note_id: String, group_id: Option<String>) -> Result<(), String> {
+    println!("set_note_group called: {} -> {:?}", note_id, group_id);
+    groups::set_note_group(&app, &note_id, group_id.as_deref())
+}
+
+/// Shows every note of a group, returning how many there are
+#[tauri::command]
+pub async fn show_group(app: tauri::AppHandle, group_id: String) -> Result<usize, String> {
+    groups::set_group_visible(&app, &group_id, true)
+}
+
+/// Hides every note of a group, returning how many there are
+#[tauri::command]
+pub async fn hide_group(app: tauri::AppHandle, group_id: String) -> Result<usize, String> {
+    groups::set_group_visible(&app, &group_id, false)
+}
+
+/// Gives every note of a group the same background color, returning how many there are
+#[tauri::command]
+pub async fn set_group_color(app: tauri::AppHandle, group_id: String, color: String) -> Result<usize, String> {
+    println!("set_group_color called: {} -> {}", group_id, color);
+    groups::set_group_color(&app, &group_id, &color).await
+}
@@ -0,0 +1,76 @@
+use tauri::Manager;
+use crate::commands::file::append_to_note;
+use crate::commands::window::find_note_data;
+use crate::hotkeys::{self, HotkeyAction, HotkeyStatus, QUICK_CAPTURE_LABEL};
+use crate::state::{load_config, save_config};
+use crate::window_manager::create_note;
+
+/// Every global shortcut with whether it is active and why not
+#[tauri::command]
+pub async fn get_hotkeys() -> Result<Vec<HotkeyStatus>, String> {
+    Ok(hotkeys::hotkey_status())
+}
+
+/// Binds a global shortcut to an action, or turns it off with `None`.
+/// The new binding is only saved if it is valid, unused and could be registered.
+#[tauri::command]
+pub async fn set_hotkey(
+    app: tauri::AppHandle,
+    action: HotkeyAction,
+    accelerator: Option<String>,
+) -> Result<Vec<HotkeyStatus>, String> {
+    println!("set_hotkey called: {:?} -> {:?}", action, accelerator);
+
+    let mut config = load_config();
+    let previous = config.hotkeys.clone();
+    action.set_binding(&mut config.hotkeys, accelerator.filter(|accelerator| !accelerator.trim().is_empty()));
+
+    // Reject invalid and duplicate combinations before touching the registered shortcuts
+    if let Some((_, Some(Err(e)))) = hotkeys::validate_hotkeys(&config.hotkeys)
+        .into_iter()
+        .find(|(checked, _)| *checked == action)
+    {
+        return Err(e);
+    }
+
+    let statuses = hotkeys::register_hotkeys(&app, &config.hotkeys);
+    if let Some(e) = statuses.iter().find(|status| status.action == action).and_then(|status| status.error.clone()) {
+        hotkeys::register_hotkeys(&app, &previous);
+        return Err(e);
+    }
+
+    save_config(&config)?;
+    // Menu shortcuts that clash with global ones are reported
+    crate::keybindings::load_keybindings();
+    Ok(statuses)
+}
+
+/// Appends quick capture text to the inbox note, creating the inbox if it does not exist yet
+#[tauri::command]
+pub async fn append_to_inbox(app: tauri::AppHandle, text: String) -> Result<(), String> {
+    println!("append_to_inbox called: {} bytes", text.len());
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+
+    let mut config = load_config();
+    let inbox_id = match config.hotkeys.inbox_note_id.clone() {
+        Some(id) if find_note_data(&id).is_some() && app.get_webview_window(&id).is_some() => id,
+        _ => {
+            let id = create_note(&app, "# Inbox\n", None)?;
+            config.hotkeys.inbox_note_id = Some(id.clone());
+            save_config(&config)?;
+            id
+        }
+    };
+
+    append_to_note(app, inbox_id, text).await
+}
+
+#[tauri::command]
+pub async fn close_quick_capture(app: tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_CAPTURE_LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -2,6 +2,10 @@ use std::fs;
 use std::path::Path;
 use base64::{Engine as _, engine::general_purpose};
 use arboard::Clipboard;
+use serde::Serialize;
+use crate::clipboard::{ExtractedImage, image_from_text, images_from_file_list, images_from_html};
+use crate::state::note_images_dir;
+use crate::vault::active_vault;
 
 /// Save pasted image to note's images folder
 #[tauri::command]
@@ -12,18 +16,9 @@ pub async fn save_pasted_image(
 ) -> Result<String, String> {
     println!("save_pasted_image called: note={}, name={}", note_path, image_name);
 
-    // Get note directory
-    let note_path = Path::new(&note_path);
-    let note_dir = note_path.parent()
-        .ok_or_else(|| "Could not get note directory".to_string())?;
-
-    // Get note filename without extension
-    let note_stem = note_path.file_stem()
-        .and_then(|s| s.to_str())
-        .ok_or_else(|| "Invalid note filename".to_string())?;
-
-    // Create images folder: ~/.peach-leaf/notes/{note_name}_images/
-    let images_dir = note_dir.join(format!("{}_images", note_stem));
+    // Images folder: ~/.peach-leaf/notes/{note_name}_images/,
+    // or the configured attachments folder in vault mode
+    let (images_dir, link_prefix) = note_images_dir(Path::new(&note_path))?;
     fs::create_dir_all(&images_dir)
         .map_err(|e| format!("Failed to create images directory: {}", e))?;
 
@@ -37,7 +32,7 @@ pub async fn save_pasted_image(
         .map_err(|e| format!("Failed to save image: {}", e))?;
 
     // Return relative path for markdown: ./{note_name}_images/{image_name}
-    let relative_path = format!("./{}_images/{}", note_stem, image_name);
+    let relative_path = format!("{}/{}", link_prefix, image_name);
     println!("Image saved successfully: {}", relative_path);
 
     Ok(relative_path)
@@ -51,6 +46,12 @@ pub async fn delete_image(
 ) -> Result<(), String> {
     println!("delete_image called: note={}, image={}", note_path, image_path);
 
+    // Vault attachments may be linked from other notes, so they are left to the vault's own tools
+    if active_vault().is_some() {
+        println!("Vault mode, keeping image: {}", image_path);
+        return Ok(());
+    }
+
     let note_path = Path::new(&note_path);
     let note_dir = note_path.parent()
         .ok_or_else(|| "Could not get note directory".to_string())?;
@@ -121,10 +122,23 @@ pub async fn cleanup_note_images(note_path: String) -> Result<(), String> {
     Ok(())
 }
 
-/// Read image file as base64 data URL for preview
+/// MIME type of an image file, based on its extension
+pub fn image_mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|s| s.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "image/png", // default
+    }
+}
+
+/// Read image file as base64 data URL for preview.
+/// When `max_width` is given, a cached thumbnail no wider than needed is served instead of the original.
 #[tauri::command]
-pub async fn read_image_as_data_url(image_path: String) -> Result<String, String> {
-    println!("read_image_as_data_url called: {}", image_path);
+pub async fn read_image_as_data_url(image_path: String, max_width: Option<u32>) -> Result<String, String> {
+    println!("read_image_as_data_url called: {}, max_width={:?}", image_path, max_width);
 
     let path = Path::new(&image_path);
 
@@ -136,17 +150,24 @@ pub async fn read_image_as_data_url(image_path: String) -> Result<String, String
     let image_bytes = fs::read(path)
         .map_err(|e| format!("Failed to read image file: {}", e))?;
 
+    // Serve a thumbnail if the preview doesn't need the full resolution
+    if let Some(max_width) = max_width {
+        match crate::thumbnail::get_or_create_thumbnail(path, &image_bytes, max_width) {
+            Ok(Some((thumbnail_bytes, mime_type))) => {
+                let base64_data = general_purpose::STANDARD.encode(&thumbnail_bytes);
+                println!("Thumbnail converted to data URL successfully ({}KB)", thumbnail_bytes.len() / 1024);
+                return Ok(format!("data:{};base64,{}", mime_type, base64_data));
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to create thumbnail, serving original: {}", e),
+        }
+    }
+
     // Convert to base64
     let base64_data = general_purpose::STANDARD.encode(&image_bytes);
 
     // Determine MIME type from extension
-    let mime_type = match path.extension().and_then(|s| s.to_str()) {
-        Some("png") => "image/png",
-        Some("jpg") | Some("jpeg") => "image/jpeg",
-        Some("gif") => "image/gif",
-        Some("webp") => "image/webp",
-        _ => "image/png", // default
-    };
+    let mime_type = image_mime_type(path);
 
     // Return as data URL
     let data_url = format!("data:{};base64,{}", mime_type, base64_data);
@@ -154,14 +175,60 @@ pub async fn read_image_as_data_url(image_path: String) -> Result<String, String
     Ok(data_url)
 }
 
-/// Read image from clipboard using native clipboard access
+/// Image pasted from the clipboard, with the clipboard format it was read from
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PastedClipboardImage {
+    /// Relative markdown paths of the saved images, in clipboard order
+    pub relative_paths: Vec<String>,
+    /// "files", "bitmap", "html" or "svg" (SVG markup copied as text)
+    pub format: String,
+}
+
+fn unique_image_name(index: usize, extension: &str) -> String {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    if index == 0 {
+        format!("image-{}.{}", timestamp, extension)
+    } else {
+        format!("image-{}-{}.{}", timestamp, index, extension)
+    }
+}
+
+/// Save extracted images through save_pasted_image and return their relative paths
+async fn save_extracted_images(note_path: &str, images: Vec<ExtractedImage>) -> Result<Vec<String>, String> {
+    let mut relative_paths = Vec::new();
+    for (index, image) in images.into_iter().enumerate() {
+        let base64_data = general_purpose::STANDARD.encode(&image.bytes);
+        let filename = unique_image_name(index, &image.extension);
+        relative_paths.push(save_pasted_image(note_path.to_string(), base64_data, filename).await?);
+    }
+    Ok(relative_paths)
+}
+
+/// Read image from clipboard using native clipboard access.
+/// Tries, in order: copied image files, raw bitmap data, HTML that is only `<img>`/inline SVG,
+/// and text that is a whole SVG document. Any other text, including text that names an image,
+/// is left for the editor to paste as text.
 #[tauri::command]
-pub async fn read_clipboard_image(note_path: String) -> Result<Option<String>, String> {
+pub async fn read_clipboard_image(note_path: String) -> Result<Option<PastedClipboardImage>, String> {
     println!("read_clipboard_image called for note: {}", note_path);
 
     let mut clipboard = Clipboard::new()
         .map_err(|e| format!("Failed to access clipboard: {}", e))?;
 
+    // Copied files come first: file managers also put the file icon on the clipboard as a bitmap
+    if let Ok(paths) = clipboard.get().file_list() {
+        let images = images_from_file_list(&paths);
+        if !images.is_empty() {
+            println!("{} image file(s) found in clipboard", images.len());
+            let relative_paths = save_extracted_images(&note_path, images).await?;
+            return Ok(Some(PastedClipboardImage { relative_paths, format: "files".to_string() }));
+        }
+    }
+
     // Try to get image from clipboard
     match clipboard.get_image() {
         Ok(img) => {
@@ -190,21 +257,36 @@ pub async fn read_clipboard_image(note_path: String) -> Result<Option<String>, S
             let base64_data = general_purpose::STANDARD.encode(&png_data);
 
             // Generate filename
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis();
-            let filename = format!("image-{}.png", timestamp);
+            let filename = unique_image_name(0, "png");
 
             // Save the image
             let relative_path = save_pasted_image(note_path, base64_data, filename).await?;
 
             println!("Image saved via native clipboard: {}", relative_path);
-            Ok(Some(relative_path))
+            return Ok(Some(PastedClipboardImage { relative_paths: vec![relative_path], format: "bitmap".to_string() }));
         }
         Err(_) => {
-            println!("No image in clipboard");
-            Ok(None)
+            println!("No bitmap in clipboard");
         }
     }
+
+    // Browsers put copied selections on the clipboard as HTML
+    if let Ok(html) = clipboard.get().html() {
+        let images = images_from_html(&html);
+        if !images.is_empty() {
+            println!("{} image(s) found in clipboard HTML", images.len());
+            let relative_paths = save_extracted_images(&note_path, images).await?;
+            return Ok(Some(PastedClipboardImage { relative_paths, format: "html".to_string() }));
+        }
+    }
+
+    // SVG markup copied from an editor or design tool arrives as plain text
+    if let Some(image) = clipboard.get_text().ok().as_deref().and_then(image_from_text) {
+        println!("SVG document found in clipboard text");
+        let relative_paths = save_extracted_images(&note_path, vec![image]).await?;
+        return Ok(Some(PastedClipboardImage { relative_paths, format: "svg".to_string() }));
+    }
+
+    println!("No image in clipboard");
+    Ok(None)
 }
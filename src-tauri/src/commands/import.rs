@@ -0,0 +1,366 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::Emitter;
+use tauri_plugin_dialog::DialogExt;
+use crate::commands::window::save_window_state_impl;
+use crate::importers;
+use crate::markdown::{rewrite_image_sources, split_front_matter};
+use crate::models::StickerData;
+use crate::palette::resolve_note_color;
+use crate::state::{get_data_dir, new_note_path, note_images_dir};
+use crate::window_manager::{generate_note_id, restore_window};
+
+/// A file that was skipped or failed during an import, with the reason
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportIssue {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Result of a bulk import
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    /// Ids of the notes that were created
+    pub imported: Vec<String>,
+    pub skipped: Vec<ImportIssue>,
+    pub failed: Vec<ImportIssue>,
+}
+
+/// Emitted as `import-progress` after each file is processed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+    /// "imported", "skipped" or "failed"
+    pub status: String,
+}
+
+/// Recursively collects markdown files, skipping hidden files and folders (e.g. `.git`, `.obsidian`)
+pub fn collect_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {:?}: {}", dir, e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let is_hidden = path.file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with('.'))
+            .unwrap_or(true);
+        if is_hidden {
+            continue;
+        }
+
+        if path.is_dir() {
+            if let Err(e) = collect_markdown_files(&path, files) {
+                eprintln!("{}", e);
+            }
+        } else if matches!(
+            path.extension().and_then(|s| s.to_str()).map(|s| s.to_ascii_lowercase()).as_deref(),
+            Some("md") | Some("markdown")
+        ) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Picks a file name inside `images_dir` that is not taken yet, by this import or by a file
+/// already there (vault attachments folders are shared between notes)
+pub fn unique_image_name(file_name: &str, images_dir: &Path, used_names: &mut HashSet<String>) -> String {
+    // Spaces would need angle-bracket links, so replace them
+    let file_name = file_name.replace(' ', "-");
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) => (stem.to_string(), format!(".{}", extension)),
+        None => (file_name.clone(), String::new()),
+    };
+
+    let mut candidate = file_name.clone();
+    let mut counter = 1;
+    while used_names.contains(&candidate) || images_dir.join(&candidate).exists() {
+        candidate = format!("{}-{}{}", stem, counter, extension);
+        counter += 1;
+    }
+    used_names.insert(candidate.clone());
+    candidate
+}
+
+/// Copies images referenced with relative links next to `source` into `images_dir`
+/// and rewrites the links to `{link_prefix}/{name}`
+fn copy_relative_images(markdown: &str, source: &Path, images_dir: &Path, link_prefix: &str) -> String {
+    let source_dir = source.parent().unwrap_or_else(|| Path::new("."));
+    let mut used_names = HashSet::new();
+
+    rewrite_image_sources(markdown, |dest| {
+        let is_relative = !dest.contains("://")
+            && !dest.starts_with("data:")
+            && !dest.starts_with('/')
+            && !dest.starts_with('#');
+        if !is_relative {
+            return None;
+        }
+
+        let decoded = urlencoding::decode(dest).map(|d| d.into_owned()).unwrap_or_else(|_| dest.to_string());
+        let image_path = source_dir.join(&decoded);
+        let file_name = image_path.file_name()?.to_str()?.to_string();
+        if !image_path.is_file() {
+            println!("Image not found, keeping link: {:?}", image_path);
+            return None;
+        }
+
+        if let Err(e) = fs::create_dir_all(images_dir) {
+            eprintln!("Failed to create images directory: {}", e);
+            return None;
+        }
+        let new_name = unique_image_name(&file_name, images_dir, &mut used_names);
+        match fs::copy(&image_path, images_dir.join(&new_name)) {
+            Ok(_) => Some(format!("{}/{}", link_prefix, new_name)),
+            Err(e) => {
+                eprintln!("Failed to copy image {:?}: {}", image_path, e);
+                None
+            }
+        }
+    })
+}
+
+/// Copies a markdown file into the notes directory as a new note: front matter is mapped onto
+/// the note's title, tags and color, and relative images are copied into the note's images folder.
+/// Returns Ok(None) if the file is empty.
+pub fn import_markdown_file(source: &Path, note_id: &str) -> Result<Option<StickerData>, String> {
+    let bytes = fs::read(source).map_err(|e| format!("Failed to read file: {}", e))?;
+    let markdown = String::from_utf8(bytes).map_err(|_| "File is not valid UTF-8".to_string())?;
+    if markdown.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let note_path = new_note_path(note_id)?;
+    let (images_dir, link_prefix) = note_images_dir(&note_path)?;
+    let (front_matter, body) = split_front_matter(&markdown);
+    let body = copy_relative_images(body, source, &images_dir, &link_prefix);
+
+    fs::write(&note_path, body).map_err(|e| format!("Failed to write note: {}", e))?;
+
+    let mut sticker_data = StickerData::new_note(note_id.to_string(), note_path.to_string_lossy().to_string());
+    sticker_data.mode = "preview".to_string();
+    if let Some(front_matter) = front_matter {
+        sticker_data.title = front_matter.title;
+        sticker_data.tags = front_matter.tags;
+        // Exact hex colors are kept, color names are mapped onto the palette
+        if let Some(color) = front_matter.color.as_deref().and_then(resolve_note_color) {
+            sticker_data.background_color = color;
+        }
+    }
+
+    Ok(Some(sticker_data))
+}
+
+/// Window position for the `index`-th imported note: diagonal cascades of 12, side by side
+fn cascade_position(index: usize) -> (i32, i32) {
+    let step = (index % 12) as i32;
+    let column = (index / 12) as i32;
+    (80 + column * 320 + step * 28, 80 + step * 28)
+}
+
+/// Import every markdown file under a folder as a note.
+/// If no folder is given, a folder dialog is shown. With `start_hidden`, notes are created hidden
+/// instead of cascaded on screen. Progress is emitted as `import-progress` events.
+/// Returns a summary of imported, skipped and failed files, or None if the user cancelled.
+#[tauri::command]
+pub async fn import_markdown_folder(
+    app: tauri::AppHandle,
+    folder_path: Option<String>,
+    start_hidden: Option<bool>,
+) -> Result<Option<ImportSummary>, String> {
+    println!("import_markdown_folder called: {:?}, start_hidden={:?}", folder_path, start_hidden);
+
+    let folder = match folder_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app.dialog()
+                .file()
+                .set_title("Import Markdown Folder")
+                .blocking_pick_folder();
+            match picked {
+                Some(path) => path.into_path().map_err(|e| format!("Invalid folder path: {}", e))?,
+                None => {
+                    println!("Folder import cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    let mut files = Vec::new();
+    collect_markdown_files(&folder, &mut files)?;
+    let total = files.len();
+    println!("Found {} markdown files in {:?}", total, folder);
+
+    let data_dir = get_data_dir();
+    let start_hidden = start_hidden.unwrap_or(false);
+    let mut summary = ImportSummary::default();
+
+    for (index, file) in files.iter().enumerate() {
+        let path_str = file.to_string_lossy().to_string();
+
+        let status = if file.starts_with(&data_dir) {
+            summary.skipped.push(ImportIssue { path: path_str.clone(), reason: "Already a PeachLeaf note".to_string() });
+            "skipped"
+        } else {
+            let note_id = generate_note_id(&app);
+            match import_markdown_file(file, &note_id) {
+                Ok(Some(mut sticker_data)) => {
+                    let (x, y) = cascade_position(summary.imported.len());
+                    sticker_data.x = x;
+                    sticker_data.y = y;
+                    sticker_data.hidden = start_hidden;
+                    restore_window(&app, sticker_data);
+                    summary.imported.push(note_id);
+                    "imported"
+                }
+                Ok(None) => {
+                    summary.skipped.push(ImportIssue { path: path_str.clone(), reason: "Empty file".to_string() });
+                    "skipped"
+                }
+                Err(e) => {
+                    eprintln!("Failed to import {:?}: {}", file, e);
+                    summary.failed.push(ImportIssue { path: path_str.clone(), reason: e });
+                    "failed"
+                }
+            }
+        };
+
+        let progress = ImportProgress {
+            current: index + 1,
+            total,
+            path: path_str,
+            status: status.to_string(),
+        };
+        if let Err(e) = app.emit("import-progress", &progress) {
+            eprintln!("Failed to emit import-progress: {}", e);
+        }
+    }
+
+    if !summary.imported.is_empty() {
+        save_window_state_impl(&app)?;
+    }
+
+    println!("Import finished: {} imported, {} skipped, {} failed",
+             summary.imported.len(), summary.skipped.len(), summary.failed.len());
+    if let Err(e) = app.emit("import-finished", &summary) {
+        eprintln!("Failed to emit import-finished: {}", e);
+    }
+
+    Ok(Some(summary))
+}
+
+/// Import notes exported from another sticky-note tool (plain text/RTF, JSON or CSV).
+/// `format` selects an importer by id ("text", "json", "csv"); otherwise it is chosen by extension.
+/// If no file is given, a file dialog is shown. Notes with a stored position are placed there
+/// when it is visible on a current monitor, and relocated otherwise.
+/// Returns a summary of the import, or None if the user cancelled.
+#[tauri::command]
+pub async fn import_notes(
+    app: tauri::AppHandle,
+    file_path: Option<String>,
+    format: Option<String>,
+) -> Result<Option<ImportSummary>, String> {
+    println!("import_notes called: {:?}, format={:?}", file_path, format);
+
+    let file = match file_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app.dialog()
+                .file()
+                .set_title("Import Notes")
+                .add_filter("Sticky Notes", &importers::supported_extensions())
+                .blocking_pick_file();
+            match picked {
+                Some(path) => path.into_path().map_err(|e| format!("Invalid file path: {}", e))?,
+                None => {
+                    println!("Note import cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    let importer = importers::find_importer(&file, format.as_deref())
+        .ok_or_else(|| format!("Unsupported note format: {:?}", file))?;
+
+    let bytes = fs::read(&file).map_err(|e| format!("Failed to read file: {}", e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let parsed = importer.parse(&content)?;
+    println!("{} importer found {} notes in {:?}, skipped {} entries",
+             importer.id(), parsed.notes.len(), file, parsed.skipped.len());
+
+    let parsed_notes = parsed.notes;
+    let total = parsed_notes.len();
+    let file_str = file.to_string_lossy().to_string();
+    let mut summary = ImportSummary {
+        skipped: parsed.skipped
+            .into_iter()
+            .map(|reason| ImportIssue { path: file_str.clone(), reason })
+            .collect(),
+        ..ImportSummary::default()
+    };
+
+    for (index, parsed) in parsed_notes.into_iter().enumerate() {
+        let entry_name = format!("{}#{}", file_str, index + 1);
+        let note_id = generate_note_id(&app);
+        let written = new_note_path(&note_id).and_then(|note_path| {
+            fs::write(&note_path, &parsed.content)
+                .map(|_| note_path)
+                .map_err(|e| format!("Failed to write note: {}", e))
+        });
+
+        let status = match written {
+            Ok(note_path) => {
+                let mut sticker_data = parsed.data;
+                sticker_data.id = note_id.clone();
+                sticker_data.file_path = note_path.to_string_lossy().to_string();
+                // restore_window checks the position against the current monitors
+                let (x, y) = parsed.position.unwrap_or_else(|| cascade_position(summary.imported.len()));
+                sticker_data.x = x;
+                sticker_data.y = y;
+                restore_window(&app, sticker_data);
+                summary.imported.push(note_id);
+                "imported"
+            }
+            Err(e) => {
+                summary.failed.push(ImportIssue { path: entry_name.clone(), reason: e });
+                "failed"
+            }
+        };
+
+        let progress = ImportProgress {
+            current: index + 1,
+            total,
+            path: entry_name,
+            status: status.to_string(),
+        };
+        if let Err(e) = app.emit("import-progress", &progress) {
+            eprintln!("Failed to emit import-progress: {}", e);
+        }
+    }
+
+    if !summary.imported.is_empty() {
+        save_window_state_impl(&app)?;
+    }
+
+    println!("Note import finished: {} imported, {} skipped, {} failed",
+             summary.imported.len(), summary.skipped.len(), summary.failed.len());
+    if let Err(e) = app.emit("import-finished", &summary) {
+        eprintln!("Failed to emit import-finished: {}", e);
+    }
+
+    Ok(Some(summary))
+}
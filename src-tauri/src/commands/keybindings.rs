@@ -0,0 +1,21 @@
+use crate::keybindings::{self, KeybindingsReport};
+use crate::menu::rebuild_menu;
+
+/// Every menu item's effective shortcut, plus problems found in keybindings.json
+#[tauri::command]
+pub async fn get_keybindings() -> Result<KeybindingsReport, String> {
+    Ok(keybindings::keybindings_report())
+}
+
+/// Sets a menu item's shortcut (`None` removes it), saves it to keybindings.json and updates the menu
+#[tauri::command]
+pub async fn set_keybinding(
+    app: tauri::AppHandle,
+    menu_id: String,
+    accelerator: Option<String>,
+) -> Result<KeybindingsReport, String> {
+    println!("set_keybinding called: {} -> {:?}", menu_id, accelerator);
+    keybindings::set_keybinding(&menu_id, accelerator)?;
+    rebuild_menu(&app);
+    Ok(keybindings::keybindings_report())
+}
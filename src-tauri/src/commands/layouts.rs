@@ -0,0 +1,30 @@
+use crate::layouts::{self, LayoutSummary};
+
+/// Saved layouts, with which one is active and which were saved for the connected monitors
+#[tauri::command]
+pub async fn list_layouts(app: tauri::AppHandle) -> Result<Vec<LayoutSummary>, String> {
+    Ok(layouts::list_layouts(&app))
+}
+
+/// Saves the current arrangement of the notes as a layout for the connected monitors.
+/// Without a name the layout is named after the monitors. Returns the updated list.
+#[tauri::command]
+pub async fn save_layout(app: tauri::AppHandle, name: Option<String>) -> Result<Vec<LayoutSummary>, String> {
+    println!("save_layout called: {:?}", name);
+    layouts::save_layout(&app, name.as_deref())?;
+    Ok(layouts::list_layouts(&app))
+}
+
+/// Switches to a layout, returning how many notes were placed
+#[tauri::command]
+pub async fn apply_layout(app: tauri::AppHandle, name: String) -> Result<usize, String> {
+    println!("apply_layout called: {}", name);
+    layouts::apply_layout(&app, &name)
+}
+
+#[tauri::command]
+pub async fn delete_layout(app: tauri::AppHandle, name: String) -> Result<Vec<LayoutSummary>, String> {
+    println!("delete_layout called: {}", name);
+    layouts::delete_layout(&app, &name)?;
+    Ok(layouts::list_layouts(&app))
+}
@@ -2,3 +2,15 @@ pub mod file;
 pub mod window;
 pub mod color;
 pub mod image;
+pub mod clipboard;
+pub mod export;
+pub mod bundle;
+pub mod import;
+pub mod vault;
+pub mod api;
+pub mod hotkeys;
+pub mod keybindings;
+pub mod layouts;
+pub mod groups;
+pub mod reminders;
+pub mod tasks;
@@ -0,0 +1,34 @@
+use crate::models::{Reminder, ReminderRepeat};
+use crate::reminders::{self, ReminderSummary, SystemClock};
+
+/// Reminders of the open notes, soonest first
+#[tauri::command]
+pub async fn list_reminders() -> Result<Vec<ReminderSummary>, String> {
+    Ok(reminders::list_reminders())
+}
+
+/// Reminds of a note at a time, in milliseconds since the Unix epoch, once or repeating.
+/// A repeating reminder starting in the past begins at its next occurrence. Returns the reminder.
+#[tauri::command]
+pub async fn set_reminder(
+    app: tauri::AppHandle,
+    note_id: String,
+    at: i64,
+    repeat: Option<ReminderRepeat>,
+) -> Result<Reminder, String> {
+    let reminder = reminders::new_reminder(&SystemClock, at, repeat.unwrap_or_default())?;
+    reminders::set_reminder(&app, &note_id, Some(reminder.clone()))?;
+    Ok(reminder)
+}
+
+#[tauri::command]
+pub async fn clear_reminder(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+    reminders::set_reminder(&app, &note_id, None)
+}
+
+/// Unpins a note that its reminder brought to the front
+#[tauri::command]
+pub async fn dismiss_reminder(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+    println!("dismiss_reminder called: {}", note_id);
+    reminders::dismiss_reminder(&app, &note_id)
+}
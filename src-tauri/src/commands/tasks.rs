@@ -0,0 +1,36 @@
+use crate::commands::file::{notify_content_changed, read_file, write_file};
+use crate::commands::window::find_note_data;
+use crate::tasks::{self, NoteTasks};
+
+/// Task items of every open note, grouped by note
+#[tauri::command]
+pub async fn list_tasks() -> Result<Vec<NoteTasks>, String> {
+    Ok(tasks::list_tasks())
+}
+
+/// Checks or unchecks the task on a line (1-based) of a note, leaving the rest of the file as it is.
+/// With `text`, fails if the line is no longer that task. Returns the note's tasks after the change.
+#[tauri::command]
+pub async fn toggle_task(
+    app: tauri::AppHandle,
+    note_id: String,
+    line: usize,
+    checked: bool,
+    text: Option<String>,
+) -> Result<Vec<tasks::TaskItem>, String> {
+    println!("toggle_task called: {} line {} -> {}", note_id, line, checked);
+    let note = find_note_data(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let content = read_file(app.clone(), note.file_path.clone()).await?;
+    // The note may have been followed to a new path while reading
+    let file_path = find_note_data(&note_id).map(|data| data.file_path).unwrap_or(note.file_path);
+
+    let updated = tasks::set_task_checked(&content, line, checked, text.as_deref())?;
+    write_file(app.clone(), file_path, updated.clone()).await?;
+    notify_content_changed(&app, &note_id);
+    Ok(tasks::extract_tasks(&note_id, &updated))
+}
+
+#[tauri::command]
+pub async fn open_tasks_window(app: tauri::AppHandle) -> Result<(), String> {
+    tasks::open_tasks_window(&app)
+}
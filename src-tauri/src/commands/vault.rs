@@ -0,0 +1,124 @@
+use std::path::{Component, Path, PathBuf};
+use tauri::Manager;
+use tauri_plugin_dialog::DialogExt;
+use crate::commands::window::{WINDOW_METADATA, save_window_state_impl};
+use crate::models::VaultConfig;
+use crate::state::{load_app_state, load_config, save_config};
+use crate::window_manager::{create_main_window, is_note_window, restore_window};
+
+/// Attachments folders must stay inside the vault (or the note's folder)
+fn validate_attachments_folder(folder: &str) -> Result<(), String> {
+    let path = Path::new(folder);
+    if folder.trim().is_empty()
+        || path.is_absolute()
+        || path.components().any(|c| matches!(c, Component::ParentDir | Component::Prefix(_)))
+    {
+        return Err(format!("Invalid attachments folder: {}", folder));
+    }
+    Ok(())
+}
+
+/// Saves and closes every note window, applies the new notes directory setting,
+/// then restores the notes saved for it
+fn switch_notes_directory(app: &tauri::AppHandle, vault: Option<VaultConfig>) -> Result<(), String> {
+    save_window_state_impl(app)?;
+
+    for (label, window) in app.webview_windows() {
+        if is_note_window(&label) {
+            let _ = window.destroy();
+        }
+    }
+    WINDOW_METADATA.lock().unwrap().clear();
+
+    let mut config = load_config();
+    config.vault = vault;
+    save_config(&config)?;
+    // Each notes directory has its own groups
+    crate::groups::unload_groups();
+
+    let state = load_app_state()?;
+    if state.windows.is_empty() {
+        println!("No saved windows for this notes directory, creating default window");
+        create_main_window(app).map_err(|e| e.to_string())?;
+    } else {
+        println!("Restoring {} saved windows", state.windows.len());
+        // Each notes directory has its own layouts
+        let layout = crate::layouts::select_layout_for_monitors(app);
+        for window_data in state.windows {
+            let window_data = match &layout {
+                Some(layout) => layout.place(window_data),
+                None => window_data,
+            };
+            restore_window(app, window_data);
+        }
+    }
+
+    crate::menu::rebuild_menu(app);
+    save_window_state_impl(app)
+}
+
+#[tauri::command]
+pub async fn get_vault_config() -> Result<Option<VaultConfig>, String> {
+    Ok(load_config().vault)
+}
+
+/// Use an existing markdown folder (e.g. a team vault) as the notes directory.
+/// If no path is given, a folder dialog is shown. Returns the new vault config, or None if cancelled.
+#[tauri::command]
+pub async fn set_vault_folder(
+    app: tauri::AppHandle,
+    folder_path: Option<String>,
+    attachments_folder: Option<String>,
+) -> Result<Option<VaultConfig>, String> {
+    println!("set_vault_folder called: {:?}, attachments={:?}", folder_path, attachments_folder);
+
+    let folder = match folder_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app.dialog()
+                .file()
+                .set_title("Choose Markdown Vault")
+                .blocking_pick_folder();
+            match picked {
+                Some(path) => path.into_path().map_err(|e| format!("Invalid folder path: {}", e))?,
+                None => {
+                    println!("Vault selection cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    if !folder.is_dir() {
+        return Err(format!("Not a folder: {:?}", folder));
+    }
+
+    // Keep the previous attachments setting when only the folder changes
+    let attachments_folder = attachments_folder
+        .or_else(|| load_config().vault.map(|vault| vault.attachments_folder))
+        .unwrap_or_else(|| "attachments".to_string());
+    validate_attachments_folder(&attachments_folder)?;
+
+    let vault = VaultConfig {
+        path: folder.to_string_lossy().to_string(),
+        attachments_folder,
+    };
+    switch_notes_directory(&app, Some(vault.clone()))?;
+
+    println!("Vault mode enabled: {}", vault.path);
+    Ok(Some(vault))
+}
+
+/// Leave vault mode and go back to PeachLeaf's own notes folder
+#[tauri::command]
+pub async fn use_default_notes_folder(app: tauri::AppHandle) -> Result<(), String> {
+    println!("use_default_notes_folder called");
+
+    if load_config().vault.is_none() {
+        return Ok(());
+    }
+    switch_notes_directory(&app, None)?;
+
+    println!("Vault mode disabled");
+    Ok(())
+}
@@ -1,13 +1,261 @@
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use tauri::{Manager, Emitter};
-use crate::models::{AppState, StickerData};
+use crate::fonts::{
+    clamp_font_size, clamp_line_height, normalize_font_family, DEFAULT_FONT_FAMILY, DEFAULT_FONT_SIZE, DEFAULT_LINE_HEIGHT,
+};
+use crate::markdown::note_title;
+use crate::models::{AppState, NoteSummary, StickerData};
+use crate::placement::{monitor_relative_position, MonitorGeometry};
 use crate::state::{load_app_state, save_app_state, get_notes_dir, ensure_notes_dir};
+use crate::vault::relocate_note;
+use crate::window_manager::is_note_window;
 
 // Store for window metadata (background colors, modes, etc.)
 pub static WINDOW_METADATA: once_cell::sync::Lazy<Arc<Mutex<HashMap<String, StickerData>>>> =
     once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
 
+/// Looks up a note's data: live window metadata first, then the saved state
+pub fn find_note_data(note_id: &str) -> Option<StickerData> {
+    if let Some(data) = WINDOW_METADATA.lock().unwrap().get(note_id) {
+        return Some(data.clone());
+    }
+
+    load_app_state()
+        .ok()
+        .and_then(|state| state.windows.into_iter().find(|w| w.id == note_id))
+}
+
+/// In vault mode, follows an open note whose file another tool renamed or moved:
+/// updates its metadata and state and tells its window. Returns the new path.
+pub fn follow_moved_note(app: &tauri::AppHandle, file_path: &str) -> Option<String> {
+    if Path::new(file_path).exists() {
+        return None;
+    }
+
+    let (note_id, claimed) = {
+        let metadata = WINDOW_METADATA.lock().unwrap();
+        let note_id = metadata.values().find(|data| data.file_path == file_path)?.id.clone();
+        let claimed: HashSet<PathBuf> = metadata.values().map(|data| PathBuf::from(&data.file_path)).collect();
+        (note_id, claimed)
+    };
+
+    let new_path = relocate_note(&note_id, Path::new(file_path), &claimed)?
+        .to_string_lossy()
+        .to_string();
+
+    if let Some(data) = WINDOW_METADATA.lock().unwrap().get_mut(&note_id) {
+        data.file_path = new_path.clone();
+    }
+
+    if let Err(e) = app.emit("note-file-moved", serde_json::json!({ "id": note_id, "filePath": new_path })) {
+        eprintln!("Failed to emit note-file-moved event: {}", e);
+    }
+    if let Err(e) = save_window_state_impl(app) {
+        eprintln!("Failed to save window state after note moved: {}", e);
+    }
+
+    Some(new_path)
+}
+
+/// A note's id, title and look, for listing it outside its window
+pub fn note_summary(note: &StickerData) -> NoteSummary {
+    let title = note.title.clone().or_else(|| note.content_title.clone()).or_else(|| {
+        fs::read_to_string(&note.file_path).ok().and_then(|markdown| note_title(&markdown))
+    });
+    summary_with_title(note, title)
+}
+
+fn summary_with_title(note: &StickerData, title: Option<String>) -> NoteSummary {
+    NoteSummary {
+        id: note.id.clone(),
+        title,
+        file_path: note.file_path.clone(),
+        background_color: note.background_color.clone(),
+        hidden: note.hidden,
+        group: note.group.clone(),
+    }
+}
+
+/// Caches the title of the open note whose file was written. Returns whether it changed.
+pub fn cache_note_title(file_path: &str, markdown: &str) -> bool {
+    let mut metadata = WINDOW_METADATA.lock().unwrap();
+    let Some(note) = metadata.values_mut().find(|note| note.file_path == file_path) else {
+        return false;
+    };
+    let title = note_title(markdown);
+    if note.content_title == title {
+        return false;
+    }
+    note.content_title = title;
+    true
+}
+
+/// Lists the open notes in the tray and the Window menu with their cached titles.
+/// Called when notes open, close, show, hide or are retitled; neither list is rebuilt if nothing changed.
+pub fn refresh_note_lists(app: &tauri::AppHandle) {
+    let mut notes: Vec<NoteSummary> = {
+        let metadata = WINDOW_METADATA.lock().unwrap();
+        app.webview_windows()
+            .values()
+            .filter_map(|window| {
+                let note = metadata.get(window.label())?;
+                let mut summary = summary_with_title(note, note.title.clone().or_else(|| note.content_title.clone()));
+                summary.hidden = !window.is_visible().unwrap_or(true);
+                Some(summary)
+            })
+            .collect()
+    };
+    notes.sort_by(|a, b| a.id.cmp(&b.id));
+    crate::tray::update_tray_notes(app, &notes);
+    crate::menu::update_window_menu_notes(app, &notes);
+}
+
+fn note_window(app: &tauri::AppHandle, note_id: &str) -> Result<tauri::WebviewWindow, String> {
+    app.get_webview_window(note_id)
+        .filter(|window| is_note_window(window.label()))
+        .ok_or_else(|| format!("Note not found: {}", note_id))
+}
+
+/// Every note with its title and visibility
+#[tauri::command]
+pub async fn list_notes(app: tauri::AppHandle) -> Result<Vec<NoteSummary>, String> {
+    // Positions and visibility of open windows are only in memory until saved
+    save_window_state_impl(&app)?;
+    Ok(load_app_state()?.windows.iter().map(note_summary).collect())
+}
+
+/// Creates a note with the given content and background color. Returns its id.
+#[tauri::command]
+pub async fn create_note(
+    app: tauri::AppHandle,
+    content: Option<String>,
+    background_color: Option<String>,
+) -> Result<String, String> {
+    println!("create_note called: {} bytes, color={:?}", content.as_deref().map_or(0, str::len), background_color);
+    crate::window_manager::create_note(&app, content.as_deref().unwrap_or(""), background_color)
+}
+
+/// Shows a note (also if hidden or minimized) and brings it to the front
+#[tauri::command]
+pub async fn focus_note(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+    println!("focus_note called: {}", note_id);
+    let window = note_window(&app, &note_id)?;
+    let was_hidden = !window.is_visible().unwrap_or(true);
+
+    window.show().map_err(|e| e.to_string())?;
+    let _ = window.unminimize();
+    window.set_focus().map_err(|e| e.to_string())?;
+
+    if was_hidden {
+        save_window_state_impl(&app)?;
+        refresh_note_lists(&app);
+    }
+    Ok(())
+}
+
+/// Hides a note's window without closing the note
+#[tauri::command]
+pub async fn hide_note(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+    println!("hide_note called: {}", note_id);
+    note_window(&app, &note_id)?.hide().map_err(|e| e.to_string())?;
+    refresh_note_lists(&app);
+    save_window_state_impl(&app)
+}
+
+/// Closes a note for good, without asking: deletes its file like closing it from its window does
+#[tauri::command]
+pub async fn delete_note(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+    println!("delete_note called: {}", note_id);
+    let window = note_window(&app, &note_id)?;
+    crate::commands::file::delete_note_file(note_id.clone()).await?;
+    // State is saved once the window is destroyed
+    window.destroy().map_err(|e| e.to_string())
+}
+
+/// Changes a note's background color the way the color picker does
+#[tauri::command]
+pub async fn set_note_color(app: tauri::AppHandle, note_id: String, color: String) -> Result<(), String> {
+    println!("set_note_color called: {} -> {}", note_id, color);
+    let window = note_window(&app, &note_id)?;
+    update_window_metadata(note_id.clone(), Some(color.clone()), None, None).await?;
+    window.emit(&format!("color-selected-{}", note_id), serde_json::json!({ "color": color }))
+        .map_err(|e| e.to_string())?;
+    save_window_state_impl(&app)
+}
+
+/// Changes a note's font size, font family and line height; values left out stay as they are.
+/// Sizes and line heights are clamped to the supported range.
+#[tauri::command]
+pub async fn set_note_font(
+    app: tauri::AppHandle,
+    note_id: String,
+    font_size: Option<u32>,
+    font_family: Option<String>,
+    line_height: Option<f64>,
+) -> Result<(), String> {
+    println!("set_note_font called: {} -> size={:?}, family={:?}, line_height={:?}", note_id, font_size, font_family, line_height);
+    let window = note_window(&app, &note_id)?;
+    let font_family = font_family.map(|family| normalize_font_family(&family)).transpose()?;
+    let font_size = font_size.map(|size| clamp_font_size(size as i64));
+    update_window_metadata(note_id.clone(), None, None, font_size).await?;
+
+    let (font_size, font_family, line_height) = {
+        let mut metadata = WINDOW_METADATA.lock().unwrap();
+        let data = metadata.get_mut(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+        if let Some(font_family) = font_family {
+            data.font_family = font_family;
+        }
+        if let Some(line_height) = line_height {
+            data.line_height = clamp_line_height(line_height);
+        }
+        (data.font_size, data.font_family.clone(), data.line_height)
+    };
+
+    window.emit(&format!("font-changed-{}", note_id), serde_json::json!({
+        "fontSize": font_size,
+        "fontFamily": font_family,
+        "lineHeight": line_height,
+    }))
+        .map_err(|e| e.to_string())?;
+
+    // The Style menu shows the focused note's font
+    if window.is_focused().unwrap_or(false) {
+        crate::menu::update_style_menu(&app, font_size, &font_family, line_height);
+    }
+    save_window_state_impl(&app)
+}
+
+/// Tiles the visible notes on the focused note's monitor into a grid, or only a group's notes.
+/// Returns how many were moved.
+#[tauri::command]
+pub async fn tile_notes(app: tauri::AppHandle, group_id: Option<String>) -> Result<usize, String> {
+    println!("tile_notes called: group={:?}", group_id);
+    crate::window_manager::tile_notes(&app, group_id.as_deref())
+}
+
+/// Cascades the visible notes on the focused note's monitor, or only a group's notes.
+/// Returns how many were moved.
+#[tauri::command]
+pub async fn cascade_notes(app: tauri::AppHandle, group_id: Option<String>) -> Result<usize, String> {
+    println!("cascade_notes called: group={:?}", group_id);
+    crate::window_manager::cascade_notes(&app, group_id.as_deref())
+}
+
+/// Lines up the given notes (or the visible notes on the focused note's monitor) with the focused one.
+/// Returns how many were moved.
+#[tauri::command]
+pub async fn align_notes(
+    app: tauri::AppHandle,
+    note_ids: Option<Vec<String>>,
+    alignment: crate::arrange::Alignment,
+) -> Result<usize, String> {
+    println!("align_notes called: {:?} {:?}", alignment, note_ids);
+    crate::window_manager::align_notes(&app, &note_ids.unwrap_or_default(), alignment)
+}
+
 #[tauri::command]
 pub async fn save_window_state(app: tauri::AppHandle) -> Result<(), String> {
     save_window_state_impl(&app)
@@ -27,19 +275,29 @@ pub async fn get_window_data(window_label: String) -> Result<Option<StickerData>
 }
 
 #[tauri::command]
-pub async fn on_window_focus(window_label: String) -> Result<(), String> {
-    use crate::menu::update_font_menu_checks;
+pub async fn on_window_focus(app: tauri::AppHandle, window_label: String) -> Result<(), String> {
+    use crate::menu::{update_style_menu, update_window_menu_checks};
 
     println!("on_window_focus called for '{}'", window_label);
 
-    // Get window metadata to update menu checks
-    let metadata = WINDOW_METADATA.lock().unwrap();
-    if let Some(data) = metadata.get(&window_label) {
-        println!("Updating font menu checks for window {} with font_size={}", window_label, data.font_size);
-        update_font_menu_checks(data.font_size);
+    // Returning to a note is when renames made in other tools are noticed
+    let file_path = WINDOW_METADATA.lock().unwrap().get(&window_label).map(|data| data.file_path.clone());
+    if let Some(file_path) = file_path {
+        follow_moved_note(&app, &file_path);
+    }
+
+    update_window_menu_checks(&window_label);
+
+    // Get window metadata to update the Style menu
+    let font = WINDOW_METADATA.lock().unwrap()
+        .get(&window_label)
+        .map(|data| (data.font_size, data.font_family.clone(), data.line_height));
+    if let Some((font_size, font_family, line_height)) = font {
+        println!("Updating style menu for window {} with font_size={}, font_family={}", window_label, font_size, font_family);
+        update_style_menu(&app, font_size, &font_family, line_height);
     } else {
-        // Default to medium (14px) if no metadata
-        update_font_menu_checks(14);
+        // Defaults if no metadata
+        update_style_menu(&app, DEFAULT_FONT_SIZE, DEFAULT_FONT_FAMILY, DEFAULT_LINE_HEIGHT);
     }
 
     Ok(())
@@ -88,9 +346,18 @@ pub async fn update_window_metadata(
             text_color: "#333333".to_string(),
             mode: mode.unwrap_or_else(|| "edit".to_string()),
             font_size: font_size.unwrap_or(14),
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
+            line_height: DEFAULT_LINE_HEIGHT,
             monitor_name: None,
             monitor_position: None,
             monitor_size: None,
+            monitor_scale_factor: None,
+            title: None,
+            tags: Vec::new(),
+            hidden: false,
+            group: None,
+            reminder: None,
+            content_title: None,
         });
     }
 
@@ -119,9 +386,14 @@ pub async fn create_sticker_window(
     .map_err(|e| e.to_string())?;
 
     // Send sticker data to the window
-    window.emit("init-sticker", sticker_data)
+    window.emit("init-sticker", &sticker_data)
         .map_err(|e| e.to_string())?;
 
+    let mut sticker_data = sticker_data;
+    sticker_data.content_title = fs::read_to_string(&sticker_data.file_path).ok().and_then(|markdown| note_title(&markdown));
+    WINDOW_METADATA.lock().unwrap().entry(sticker_data.id.clone()).or_insert(sticker_data);
+    refresh_note_lists(&app);
+
     Ok(())
 }
 
@@ -138,8 +410,8 @@ pub fn save_window_state_impl(app: &tauri::AppHandle) -> Result<(), String> {
     windows.sort_by_key(|(label, _)| label.as_str());
 
     for (label, window) in windows {
-        // Skip color picker window
-        if label.as_str() == "color-picker" {
+        // Skip the color picker and quick capture windows
+        if !is_note_window(label) {
             continue;
         }
 
@@ -150,9 +422,7 @@ pub fn save_window_state_impl(app: &tauri::AppHandle) -> Result<(), String> {
         // Get window scale factor
         let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
 
-        // Convert to logical pixels
-        let x = (position.x as f64 / scale_factor) as i32;
-        let y = (position.y as f64 / scale_factor) as i32;
+        // Convert the size to logical pixels
         let width = (size.width as f64 / scale_factor) as u32;
         let height = (size.height as f64 / scale_factor) as u32;
 
@@ -162,6 +432,11 @@ pub fn save_window_state_impl(app: &tauri::AppHandle) -> Result<(), String> {
             .map(|data| data.background_color.clone())
             .unwrap_or_else(|| "#FEFCE8".to_string());
 
+        let text_color = metadata
+            .get(label.as_str())
+            .map(|data| data.text_color.clone())
+            .unwrap_or_else(|| "#333333".to_string());
+
         let mode = metadata
             .get(label.as_str())
             .map(|data| data.mode.clone())
@@ -172,25 +447,50 @@ pub fn save_window_state_impl(app: &tauri::AppHandle) -> Result<(), String> {
             .map(|data| data.font_size)
             .unwrap_or(14);
 
-        // Get monitor information
-        let (monitor_name, monitor_position, monitor_size) = match window.current_monitor() {
+        let (font_family, line_height) = metadata
+            .get(label.as_str())
+            .map(|data| (data.font_family.clone(), data.line_height))
+            .unwrap_or_else(|| (DEFAULT_FONT_FAMILY.to_string(), DEFAULT_LINE_HEIGHT));
+
+        let (title, tags, group, reminder) = metadata
+            .get(label.as_str())
+            .map(|data| (data.title.clone(), data.tags.clone(), data.group.clone(), data.reminder.clone()))
+            .unwrap_or_default();
+
+        let hidden = !window.is_visible().unwrap_or(true);
+
+        // Position relative to the window's monitor, in the monitor's logical pixels
+        let (x, y, monitor_name, monitor_position, monitor_size, monitor_scale_factor) = match window.current_monitor() {
             Ok(Some(monitor)) => {
-                let name = monitor.name().map(|s| s.to_string());
-                let pos = monitor.position();
-                let size = monitor.size();
+                let monitor = MonitorGeometry::from_monitor(&monitor);
+                let (x, y) = monitor_relative_position((position.x, position.y), &monitor);
                 (
-                    name,
-                    Some((pos.x, pos.y)),
-                    Some((size.width, size.height))
+                    x,
+                    y,
+                    monitor.name,
+                    Some(monitor.position),
+                    Some(monitor.size),
+                    Some(monitor.scale_factor)
                 )
             }
-            _ => (None, None, None)
+            _ => (
+                (position.x as f64 / scale_factor) as i32,
+                (position.y as f64 / scale_factor) as i32,
+                None,
+                None,
+                None,
+                None
+            )
         };
 
-        // Create file path for this window using permanent directory
-        let notes_dir = get_notes_dir();
-        let file_path = notes_dir.join(format!("{}.md", label));
-        let file_path_str = file_path.to_string_lossy().to_string();
+        // Use the note's own file path (linked notes live outside the notes directory),
+        // falling back to the permanent notes directory
+        let file_path_str = metadata
+            .get(label.as_str())
+            .map(|data| data.file_path.clone())
+            .unwrap_or_else(|| {
+                get_notes_dir().join(format!("{}.md", label)).to_string_lossy().to_string()
+            });
 
         // Debug: check if metadata exists for this window
         if metadata.contains_key(label.as_str()) {
@@ -199,7 +499,7 @@ pub fn save_window_state_impl(app: &tauri::AppHandle) -> Result<(), String> {
             println!("No metadata found for window {}, using defaults", label);
         }
 
-        let sticker_data = StickerData {
+        let mut sticker_data = StickerData {
             id: label.to_string(),
             file_path: file_path_str.clone(),
             x,
@@ -207,13 +507,24 @@ pub fn save_window_state_impl(app: &tauri::AppHandle) -> Result<(), String> {
             width,
             height,
             background_color: background_color.clone(),
-            text_color: "#333333".to_string(),
+            text_color,
             mode: mode.clone(),
             font_size,
+            font_family,
+            line_height,
             monitor_name: monitor_name.clone(),
             monitor_position,
             monitor_size,
+            monitor_scale_factor,
+            title,
+            tags,
+            hidden,
+            group,
+            reminder,
+            content_title: None,
         };
+        // A note moved off a disconnected monitor is saved where it goes back to
+        crate::monitors::keep_rescued_placement(&mut sticker_data);
 
         windows_data.push(sticker_data);
         println!("Saved window {}: position=({}, {}), size=({}x{}), color={}, mode={}, font_size={}, path={}",
@@ -222,8 +533,9 @@ pub fn save_window_state_impl(app: &tauri::AppHandle) -> Result<(), String> {
 
     // Sort windows_data by id to ensure consistent order in state.json
     windows_data.sort_by(|a, b| a.id.cmp(&b.id));
+    drop(metadata);
 
-    save_app_state(windows_data)?;
+    save_app_state(AppState { windows: windows_data, groups: crate::groups::groups() })?;
     println!("Window state saved successfully");
     Ok(())
 }
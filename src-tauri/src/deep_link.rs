@@ -0,0 +1,92 @@
+use tauri::Url;
+use crate::commands::window::focus_note;
+use crate::palette::resolve_note_color;
+use crate::window_manager::create_note;
+
+pub const URL_SCHEME: &str = "peachleaf";
+
+/// What a `peachleaf://` link asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    /// `peachleaf://note/<id>`
+    FocusNote(String),
+    /// `peachleaf://new?text=...&color=...`
+    NewNote { text: Option<String>, color: Option<String> },
+}
+
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.split_once(':').is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case(URL_SCHEME))
+}
+
+pub fn parse_deep_link(url: &str) -> Result<DeepLink, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid link {}: {}", url, e))?;
+    if parsed.scheme() != URL_SCHEME {
+        return Err(format!("Not a {}:// link: {}", URL_SCHEME, url));
+    }
+
+    // In `peachleaf://note/x`, "note" is the URL's host and "x" its path
+    let mut segments: Vec<String> = parsed.host_str().map(|host| host.to_string()).into_iter().collect();
+    segments.extend(
+        parsed.path_segments().into_iter().flatten()
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| urlencoding::decode(segment).map(|s| s.into_owned()).unwrap_or_else(|_| segment.to_string())),
+    );
+
+    match segments.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["note", id] => Ok(DeepLink::FocusNote(id.to_string())),
+        ["new"] => {
+            let mut text = None;
+            let mut color = None;
+            for (key, value) in parsed.query_pairs() {
+                match key.as_ref() {
+                    "text" => text = Some(value.into_owned()),
+                    "color" => {
+                        color = Some(resolve_note_color(&value).ok_or_else(|| format!("Unknown color: {}", value))?)
+                    }
+                    _ => {}
+                }
+            }
+            Ok(DeepLink::NewNote { text, color })
+        }
+        _ => Err(format!("Unknown link: {}", url)),
+    }
+}
+
+/// Opens a `peachleaf://` link in this instance
+pub fn handle_deep_link(app: &tauri::AppHandle, url: &str) -> Result<(), String> {
+    println!("Handling deep link: {}", url);
+    match parse_deep_link(url)? {
+        DeepLink::FocusNote(id) => tauri::async_runtime::block_on(focus_note(app.clone(), id)),
+        DeepLink::NewNote { text, color } => {
+            create_note(app, text.as_deref().unwrap_or(""), color).map(|_| ())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_links_in_any_case() {
+        assert!(is_deep_link("peachleaf://note/note-1"));
+        assert!(is_deep_link("PeachLeaf://new?text=hi"));
+        assert!(!is_deep_link("peachleaf"));
+        assert!(!is_deep_link("peachleafs://note/note-1"));
+        assert!(!is_deep_link("https://example.com"));
+    }
+
+    #[test]
+    fn ignores_non_ascii_paths() {
+        assert!(!is_deep_link("/Users/김철수/note.md"));
+        assert!(!is_deep_link("C:\\메모\\note.md"));
+        assert!(!is_deep_link("피치리프://note/note-1"));
+    }
+
+    #[test]
+    fn parses_note_and_new_links() {
+        assert_eq!(parse_deep_link("peachleaf://note/note-1"), Ok(DeepLink::FocusNote("note-1".to_string())));
+        assert!(matches!(parse_deep_link("peachleaf://new"), Ok(DeepLink::NewNote { .. })));
+        assert!(parse_deep_link("https://example.com").is_err());
+    }
+}
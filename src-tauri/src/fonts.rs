@@ -0,0 +1,70 @@
+/// Font size a note gets back from Reset Font Size
+pub const DEFAULT_FONT_SIZE: u32 = 14;
+pub const MIN_FONT_SIZE: u32 = 8;
+pub const MAX_FONT_SIZE: u32 = 72;
+
+/// Stored in `StickerData::font_family` for the platform's UI font
+pub const DEFAULT_FONT_FAMILY: &str = "system";
+
+/// Font families every platform has, by stored value and menu label.
+/// Any other value is the name of an installed font.
+pub const GENERIC_FONT_FAMILIES: [(&str, &str); 3] = [
+    (DEFAULT_FONT_FAMILY, "System"),
+    ("serif", "Serif"),
+    ("monospace", "Monospace"),
+];
+
+pub const DEFAULT_LINE_HEIGHT: f64 = 1.6;
+pub const MIN_LINE_HEIGHT: f64 = 1.0;
+pub const MAX_LINE_HEIGHT: f64 = 3.0;
+
+/// Line heights offered in the Style menu, by value and menu label
+pub const LINE_HEIGHTS: [(f64, &str); 4] = [
+    (1.2, "Tight"),
+    (1.6, "Normal"),
+    (2.0, "Relaxed"),
+    (2.4, "Loose"),
+];
+
+/// Keeps a font size within the supported range
+pub fn clamp_font_size(size: i64) -> u32 {
+    size.clamp(MIN_FONT_SIZE as i64, MAX_FONT_SIZE as i64) as u32
+}
+
+/// Keeps a line height within the supported range, rounded to hundredths
+pub fn clamp_line_height(line_height: f64) -> f64 {
+    if !line_height.is_finite() {
+        return DEFAULT_LINE_HEIGHT;
+    }
+    (line_height.clamp(MIN_LINE_HEIGHT, MAX_LINE_HEIGHT) * 100.0).round() / 100.0
+}
+
+/// Whether two line heights are the same once rounded, e.g. to check a menu item
+pub fn same_line_height(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.005
+}
+
+/// Validates a font family: a generic family name (any case) or an installed font's name.
+/// Names go into the note's CSS, so quotes and CSS punctuation are refused.
+pub fn normalize_font_family(family: &str) -> Result<String, String> {
+    let family = family.trim();
+    if family.is_empty() {
+        return Err("Font family is empty".to_string());
+    }
+    if let Some((generic, _)) = GENERIC_FONT_FAMILIES.iter().find(|(generic, _)| generic.eq_ignore_ascii_case(family)) {
+        return Ok(generic.to_string());
+    }
+    if family.chars().any(|c| matches!(c, '"' | '\'' | ';' | '{' | '}' | '<' | '>' | '\\') || c.is_control()) {
+        return Err(format!("Invalid font family: {}", family));
+    }
+    Ok(family.to_string())
+}
+
+/// A font family's menu label
+pub fn font_family_label(family: &str) -> &str {
+    GENERIC_FONT_FAMILIES
+        .iter()
+        .find(|(generic, _)| *generic == family)
+        .map(|(_, label)| *label)
+        .unwrap_or(family)
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use crate::commands::window::{refresh_note_lists, save_window_state_impl, WINDOW_METADATA};
+use crate::models::{NoteGroup, StickerData};
+use crate::state::load_app_state;
+
+/// Prefix of the Group menu ids that move the focused note into a group, followed by the group id
+pub const JOIN_GROUP_MENU_PREFIX: &str = "join_group_";
+pub const SHOW_GROUP_MENU_PREFIX: &str = "show_group_";
+pub const HIDE_GROUP_MENU_PREFIX: &str = "hide_group_";
+pub const TILE_GROUP_MENU_PREFIX: &str = "tile_group_";
+pub const DELETE_GROUP_MENU_PREFIX: &str = "delete_group_";
+
+// Groups of the current notes directory, read from the saved state on first use
+static GROUPS: once_cell::sync::Lazy<Mutex<Option<Vec<NoteGroup>>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+/// A group with the ids of its open notes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupSummary {
+    pub id: String,
+    pub name: String,
+    pub note_ids: Vec<String>,
+}
+
+pub fn groups() -> Vec<NoteGroup> {
+    let mut groups = GROUPS.lock().unwrap();
+    groups
+        .get_or_insert_with(|| load_app_state().map(|state| state.groups).unwrap_or_default())
+        .clone()
+}
+
+/// Forgets the loaded groups, e.g. after switching to another notes directory
+pub fn unload_groups() {
+    *GROUPS.lock().unwrap() = None;
+}
+
+fn set_groups(app: &tauri::AppHandle, groups: Vec<NoteGroup>) -> Result<(), String> {
+    *GROUPS.lock().unwrap() = Some(groups);
+    save_window_state_impl(app)?;
+    crate::menu::rebuild_menu(app);
+    Ok(())
+}
+
+fn find_group(group_id: &str) -> Result<NoteGroup, String> {
+    groups()
+        .into_iter()
+        .find(|group| group.id == group_id)
+        .ok_or_else(|| format!("Group not found: {}", group_id))
+}
+
+/// Ids of the open notes in a group, sorted
+pub fn group_members(group_id: &str) -> Vec<String> {
+    let mut members: Vec<String> = WINDOW_METADATA
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|note| note.group.as_deref() == Some(group_id))
+        .map(|note| note.id.clone())
+        .collect();
+    members.sort();
+    members
+}
+
+/// The group a note belongs to
+pub fn note_group(note_id: &str) -> Option<String> {
+    WINDOW_METADATA.lock().unwrap().get(note_id).and_then(|note| note.group.clone())
+}
+
+pub fn list_groups() -> Vec<GroupSummary> {
+    groups()
+        .into_iter()
+        .map(|group| GroupSummary { note_ids: group_members(&group.id), id: group.id, name: group.name })
+        .collect()
+}
+
+/// "Group 1", "Group 2", ...: the first one not taken
+fn default_group_name(groups: &[NoteGroup]) -> String {
+    (1..)
+        .map(|number| format!("Group {}", number))
+        .find(|name| !groups.iter().any(|group| group.name == *name))
+        .unwrap_or_default()
+}
+
+fn validate_group_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Group name is empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Puts notes into a group, or none of them if any id is unknown
+fn assign_group(metadata: &mut HashMap<String, StickerData>, group_id: &str, note_ids: &[String]) -> Result<(), String> {
+    if let Some(missing) = note_ids.iter().find(|note_id| !metadata.contains_key(note_id.as_str())) {
+        return Err(format!("Note not found: {}", missing));
+    }
+    for note_id in note_ids {
+        if let Some(note) = metadata.get_mut(note_id) {
+            note.group = Some(group_id.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Creates a group, named "Group N" if no name is given, and moves the given notes into it
+pub fn create_group(app: &tauri::AppHandle, name: Option<&str>, note_ids: &[String]) -> Result<NoteGroup, String> {
+    let mut groups = groups();
+    let name = match name {
+        Some(name) => validate_group_name(name)?,
+        None => default_group_name(&groups),
+    };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or(0);
+    let mut id = format!("group-{}", timestamp);
+    let mut counter = 1;
+    while groups.iter().any(|group| group.id == id) {
+        id = format!("group-{}-{}", timestamp, counter);
+        counter += 1;
+    }
+
+    let group = NoteGroup { id, name };
+    println!("Creating group {:?} ({}) with {} notes", group.name, group.id, note_ids.len());
+    assign_group(&mut WINDOW_METADATA.lock().unwrap(), &group.id, note_ids)?;
+    groups.push(group.clone());
+    set_groups(app, groups)?;
+    Ok(group)
+}
+
+pub fn rename_group(app: &tauri::AppHandle, group_id: &str, name: &str) -> Result<NoteGroup, String> {
+    let name = validate_group_name(name)?;
+    let mut groups = groups();
+    let group = groups
+        .iter_mut()
+        .find(|group| group.id == group_id)
+        .ok_or_else(|| format!("Group not found: {}", group_id))?;
+    group.name = name;
+    let renamed = group.clone();
+    set_groups(app, groups)?;
+    Ok(renamed)
+}
+
+/// Deletes a group; its notes stay, without a group
+pub fn delete_group(app: &tauri::AppHandle, group_id: &str) -> Result<(), String> {
+    let mut groups = groups();
+    let count = groups.len();
+    groups.retain(|group| group.id != group_id);
+    if groups.len() == count {
+        return Err(format!("Group not found: {}", group_id));
+    }
+
+    for note in WINDOW_METADATA.lock().unwrap().values_mut() {
+        if note.group.as_deref() == Some(group_id) {
+            note.group = None;
+        }
+    }
+    println!("Deleted group {}", group_id);
+    set_groups(app, groups)
+}
+
+/// Moves a note into a group, or out of its group with `None`
+pub fn set_note_group(app: &tauri::AppHandle, note_id: &str, group_id: Option<&str>) -> Result<(), String> {
+    if let Some(group_id) = group_id {
+        find_group(group_id)?;
+    }
+    {
+        let mut metadata = WINDOW_METADATA.lock().unwrap();
+        let note = metadata.get_mut(note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+        note.group = group_id.map(str::to_string);
+    }
+    println!("Note {} is now in group {:?}", note_id, group_id);
+
+    crate::menu::update_group_menu_checks();
+    save_window_state_impl(app)
+}
+
+/// Shows or hides every note of a group. Returns how many notes there are.
+pub fn set_group_visible(app: &tauri::AppHandle, group_id: &str, visible: bool) -> Result<usize, String> {
+    find_group(group_id)?;
+    let members = group_members(group_id);
+    for note_id in &members {
+        if let Some(window) = app.get_webview_window(note_id) {
+            let result = if visible { window.show() } else { window.hide() };
+            result.map_err(|e| e.to_string())?;
+        }
+    }
+    refresh_note_lists(app);
+    save_window_state_impl(app)?;
+    Ok(members.len())
+}
+
+/// Gives every note of a group the same background color. Returns how many notes there are.
+pub async fn set_group_color(app: &tauri::AppHandle, group_id: &str, color: &str) -> Result<usize, String> {
+    find_group(group_id)?;
+    let members = group_members(group_id);
+    for note_id in &members {
+        crate::commands::window::set_note_color(app.clone(), note_id.clone(), color.to_string()).await?;
+    }
+    Ok(members.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata_with(note_ids: &[&str]) -> HashMap<String, StickerData> {
+        note_ids
+            .iter()
+            .map(|id| (id.to_string(), StickerData::new_note(id.to_string(), format!("/notes/{}.md", id))))
+            .collect()
+    }
+
+    #[test]
+    fn notes_join_the_group() {
+        let mut metadata = metadata_with(&["note-1", "note-2"]);
+        assign_group(&mut metadata, "group-1", &["note-1".to_string(), "note-2".to_string()]).unwrap();
+        assert_eq!(metadata["note-1"].group.as_deref(), Some("group-1"));
+        assert_eq!(metadata["note-2"].group.as_deref(), Some("group-1"));
+    }
+
+    #[test]
+    fn unknown_note_leaves_every_note_ungrouped() {
+        let mut metadata = metadata_with(&["note-1", "note-2"]);
+        let result = assign_group(&mut metadata, "group-1", &["note-1".to_string(), "note-3".to_string(), "note-2".to_string()]);
+        assert_eq!(result, Err("Note not found: note-3".to_string()));
+        assert!(metadata.values().all(|note| note.group.is_none()));
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use crate::models::HotkeyConfig;
+use crate::window_manager::{create_new_note_backend, hide_all_notes, is_note_window, show_all_notes};
+
+pub const QUICK_CAPTURE_LABEL: &str = "quick-capture";
+
+/// Something a system-wide shortcut does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+    NewNote,
+    ToggleNotes,
+    QuickCapture,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 3] = [HotkeyAction::NewNote, HotkeyAction::ToggleNotes, HotkeyAction::QuickCapture];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::NewNote => "New Note",
+            HotkeyAction::ToggleNotes => "Show/Hide All Notes",
+            HotkeyAction::QuickCapture => "Quick Capture",
+        }
+    }
+
+    pub fn binding(self, config: &HotkeyConfig) -> Option<&String> {
+        match self {
+            HotkeyAction::NewNote => config.new_note.as_ref(),
+            HotkeyAction::ToggleNotes => config.toggle_notes.as_ref(),
+            HotkeyAction::QuickCapture => config.quick_capture.as_ref(),
+        }
+    }
+
+    pub fn set_binding(self, config: &mut HotkeyConfig, accelerator: Option<String>) {
+        match self {
+            HotkeyAction::NewNote => config.new_note = accelerator,
+            HotkeyAction::ToggleNotes => config.toggle_notes = accelerator,
+            HotkeyAction::QuickCapture => config.quick_capture = accelerator,
+        }
+    }
+}
+
+/// Whether a shortcut is active, and why not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyStatus {
+    pub action: HotkeyAction,
+    pub accelerator: Option<String>,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+// Outcome of the last registration of each shortcut
+static HOTKEY_STATUS: once_cell::sync::Lazy<Mutex<Vec<HotkeyStatus>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn parse_accelerator(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", accelerator, e))
+}
+
+/// Checks every binding, reporting invalid combinations and combinations bound twice.
+/// Returns each action's shortcut, or why it cannot be used.
+pub fn validate_hotkeys(config: &HotkeyConfig) -> Vec<(HotkeyAction, Option<Result<Shortcut, String>>)> {
+    let mut checked: Vec<(HotkeyAction, Option<Result<Shortcut, String>>)> = Vec::new();
+
+    for action in HotkeyAction::ALL {
+        let result = action.binding(config).map(|accelerator| {
+            let shortcut = parse_accelerator(accelerator)?;
+            let taken_by = checked.iter().find(|(_, other)| matches!(other, Some(Ok(other)) if *other == shortcut));
+            match taken_by {
+                Some((other, _)) => Err(format!("\"{}\" is already used for {}", accelerator, other.label())),
+                None => Ok(shortcut),
+            }
+        });
+        checked.push((action, result));
+    }
+
+    checked
+}
+
+fn run_action(app: &tauri::AppHandle, action: HotkeyAction) {
+    println!("Global shortcut: {:?}", action);
+    match action {
+        HotkeyAction::NewNote => create_new_note_backend(app),
+        HotkeyAction::ToggleNotes => {
+            let any_visible = app
+                .webview_windows()
+                .values()
+                .any(|window| is_note_window(window.label()) && window.is_visible().unwrap_or(false));
+            if any_visible {
+                hide_all_notes(app);
+            } else {
+                show_all_notes(app);
+            }
+        }
+        HotkeyAction::QuickCapture => {
+            if let Err(e) = open_quick_capture(app) {
+                eprintln!("Failed to open quick capture: {}", e);
+            }
+        }
+    }
+}
+
+fn register_shortcut(app: &tauri::AppHandle, action: HotkeyAction, shortcut: Shortcut) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                run_action(app, action);
+            }
+        })
+        // Most often the combination belongs to another application
+        .map_err(|e| format!("Could not register the shortcut, it may be taken by another application: {}", e))
+}
+
+/// (Re)registers every configured shortcut. Bindings that are invalid, duplicated or
+/// taken by another application are skipped and reported, the others still work.
+pub fn register_hotkeys(app: &tauri::AppHandle, config: &HotkeyConfig) -> Vec<HotkeyStatus> {
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        eprintln!("Failed to unregister global shortcuts: {}", e);
+    }
+
+    let statuses: Vec<HotkeyStatus> = validate_hotkeys(config)
+        .into_iter()
+        .map(|(action, checked)| {
+            let result = checked.map(|shortcut| shortcut.and_then(|shortcut| register_shortcut(app, action, shortcut)));
+            if let Some(Err(e)) = &result {
+                eprintln!("Global shortcut for {} not available: {}", action.label(), e);
+            }
+            HotkeyStatus {
+                action,
+                accelerator: action.binding(config).cloned(),
+                registered: matches!(result, Some(Ok(()))),
+                error: result.and_then(|result| result.err()),
+            }
+        })
+        .collect();
+
+    *HOTKEY_STATUS.lock().unwrap() = statuses.clone();
+    statuses
+}
+
+pub fn hotkey_status() -> Vec<HotkeyStatus> {
+    HOTKEY_STATUS.lock().unwrap().clone()
+}
+
+/// Opens the small always-on-top box whose text is appended to the inbox note
+pub fn open_quick_capture(app: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_CAPTURE_LABEL) {
+        window.show().map_err(|e| e.to_string())?;
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    WebviewWindowBuilder::new(app, QUICK_CAPTURE_LABEL, WebviewUrl::default())
+        .title("Quick Capture")
+        .inner_size(420.0, 140.0)
+        .center()
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use super::{NoteImporter, ParsedNote, ParsedNotes};
+
+/// CSV with a header row. Recognized columns: `title`, `body` (or `content`/`text`), `color`,
+/// and optionally `x`, `y`, `width`, `height`. Without a recognizable header, columns are
+/// read as title, body, color.
+pub struct CsvImporter;
+
+/// Splits CSV into records, handling quoted fields with embedded commas, quotes and newlines
+fn parse_records(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+        .into_iter()
+        .filter(|record| record.iter().any(|field| !field.trim().is_empty()))
+        .collect()
+}
+
+fn column_index(header: &[String], names: &[&str]) -> Option<usize> {
+    header.iter().position(|column| {
+        let column = column.trim().to_ascii_lowercase();
+        names.contains(&column.as_str())
+    })
+}
+
+impl NoteImporter for CsvImporter {
+    fn id(&self) -> &'static str {
+        "csv"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    fn parse(&self, content: &str) -> Result<ParsedNotes, String> {
+        let content = content.trim_start_matches('\u{feff}');
+        let records = parse_records(content);
+        let Some(header) = records.first() else {
+            return Ok(ParsedNotes::default());
+        };
+
+        let title_column = column_index(header, &["title", "name", "subject"]);
+        let body_column = column_index(header, &["body", "content", "text", "note"]);
+        let has_header = title_column.is_some() || body_column.is_some();
+
+        let (title_column, body_column, color_column) = if has_header {
+            (title_column, body_column, column_index(header, &["color", "colour", "background"]))
+        } else {
+            (Some(0), Some(1), Some(2))
+        };
+        let x_column = if has_header { column_index(header, &["x", "left"]) } else { None };
+        let y_column = if has_header { column_index(header, &["y", "top"]) } else { None };
+        let width_column = if has_header { column_index(header, &["width"]) } else { None };
+        let height_column = if has_header { column_index(header, &["height"]) } else { None };
+
+        let rows = if has_header { &records[1..] } else { &records[..] };
+        let field = |row: &Vec<String>, column: Option<usize>| -> Option<String> {
+            column.and_then(|i| row.get(i)).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+        };
+        let number = |row: &Vec<String>, column: Option<usize>| -> Option<f64> {
+            field(row, column).and_then(|s| s.parse::<f64>().ok())
+        };
+
+        let mut parsed = ParsedNotes::default();
+        for (index, row) in rows.iter().enumerate() {
+            let title = field(row, title_column);
+            let body = field(row, body_column).unwrap_or_default();
+            if title.is_none() && body.is_empty() {
+                parsed.skipped.push(format!("Row {}: no title or text", index + 1));
+                continue;
+            }
+
+            let color = field(row, color_column);
+            let mut note = ParsedNote::new(title, &body, color.as_deref());
+            if let (Some(x), Some(y)) = (number(row, x_column), number(row, y_column)) {
+                note.position = Some((x.round() as i32, y.round() as i32));
+            }
+            if let Some(width) = number(row, width_column).filter(|w| *w >= 100.0) {
+                note.data.width = width.round() as u32;
+            }
+            if let Some(height) = number(row, height_column).filter(|h| *h >= 80.0) {
+                note.data.height = height.round() as u32;
+            }
+            parsed.notes.push(note);
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_fields_keep_commas_quotes_and_newlines() {
+        let records = parse_records("title,body\r\n\"Plan, v2\",\"Say \"\"hi\"\"\nthen leave\"\r\n");
+        assert_eq!(records, vec![
+            vec!["title".to_string(), "body".to_string()],
+            vec!["Plan, v2".to_string(), "Say \"hi\"\nthen leave".to_string()],
+        ]);
+    }
+
+    #[test]
+    fn blank_lines_are_skipped_and_last_line_needs_no_newline() {
+        let records = parse_records("a,b\n\n , \nc,");
+        assert_eq!(records, vec![
+            vec!["a".to_string(), "b".to_string()],
+            vec!["c".to_string(), String::new()],
+        ]);
+    }
+
+    #[test]
+    fn rows_without_title_or_text_are_reported() {
+        let parsed = CsvImporter.parse("title,body,color\nPlan,,\n,,yellow\n").unwrap();
+        assert_eq!(parsed.notes.len(), 1);
+        assert_eq!(parsed.skipped, vec!["Row 2: no title or text".to_string()]);
+    }
+
+    #[test]
+    fn unterminated_quote_runs_to_the_end() {
+        assert_eq!(parse_records("\"open,field\nmore"), vec![vec!["open,field\nmore".to_string()]]);
+    }
+}
@@ -0,0 +1,147 @@
+use serde_json::Value;
+use super::{NoteImporter, ParsedNote, ParsedNotes};
+
+/// Generic JSON exports: an array of note objects, or an object with a `notes`/`items`/`stickies`/`data` array.
+/// Common field names are recognized, e.g. `title`/`name`, `body`/`content`/`text`,
+/// `color`/`backgroundColor`, `x`/`left`, `y`/`top`, `width`, `height`, `tags`,
+/// and nested `position: {x, y}` / `size: {width, height}` objects.
+pub struct JsonImporter;
+
+fn string_field(object: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| object.get(*key).and_then(Value::as_str))
+        .map(|s| s.to_string())
+}
+
+fn number_field(object: &Value, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|key| {
+        let value = object.get(*key)?;
+        value.as_f64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+    })
+}
+
+/// Reads a note object, or says why the value is not a note
+fn parse_note(object: &Value) -> Result<ParsedNote, String> {
+    if !object.is_object() {
+        return Err("not an object".to_string());
+    }
+    let title = string_field(object, &["title", "name", "subject"]);
+    let body = string_field(object, &["body", "content", "text", "note", "markdown"]).unwrap_or_default();
+    if title.is_none() && body.trim().is_empty() {
+        return Err("no title or text".to_string());
+    }
+
+    let color = string_field(object, &["color", "colour", "backgroundColor", "background_color", "background"]);
+    let mut note = ParsedNote::new(title, &body, color.as_deref());
+
+    let position = object.get("position").unwrap_or(object);
+    let x = number_field(position, &["x", "left"]);
+    let y = number_field(position, &["y", "top"]);
+    if let (Some(x), Some(y)) = (x, y) {
+        note.position = Some((x.round() as i32, y.round() as i32));
+    }
+
+    let size = object.get("size").unwrap_or(object);
+    if let Some(width) = number_field(size, &["width", "w"]).filter(|w| *w >= 100.0) {
+        note.data.width = width.round() as u32;
+    }
+    if let Some(height) = number_field(size, &["height", "h"]).filter(|h| *h >= 80.0) {
+        note.data.height = height.round() as u32;
+    }
+
+    if let Some(tags) = object.get("tags").and_then(Value::as_array) {
+        note.data.tags = tags.iter().filter_map(Value::as_str).map(|s| s.to_string()).collect();
+    }
+
+    Ok(note)
+}
+
+impl NoteImporter for JsonImporter {
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn parse(&self, content: &str) -> Result<ParsedNotes, String> {
+        let root: Value = serde_json::from_str(content)
+            .map_err(|e| format!("Invalid JSON: {}", e))?;
+
+        let items = match &root {
+            Value::Array(items) => items.clone(),
+            Value::Object(_) => ["notes", "items", "stickies", "data"]
+                .iter()
+                .find_map(|key| root.get(*key).and_then(Value::as_array).cloned())
+                // A single note object
+                .unwrap_or_else(|| vec![root.clone()]),
+            _ => return Err("Expected a JSON array or object of notes".to_string()),
+        };
+
+        let mut parsed = ParsedNotes::default();
+        for (index, item) in items.iter().enumerate() {
+            match parse_note(item) {
+                Ok(note) => parsed.notes.push(note),
+                Err(reason) => parsed.skipped.push(format!("Entry {}: {}", index + 1, reason)),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> ParsedNotes {
+        JsonImporter.parse(json).unwrap()
+    }
+
+    #[test]
+    fn nested_position_and_size_are_read() {
+        let parsed = parse(r#"[{"title": "Plan", "position": {"x": 10.4, "y": "20"}, "size": {"w": 320, "h": 240}}]"#);
+        let note = &parsed.notes[0];
+        assert_eq!(note.position, Some((10, 20)));
+        assert_eq!((note.data.width, note.data.height), (320, 240));
+    }
+
+    #[test]
+    fn flat_fields_are_used_without_nested_objects() {
+        let parsed = parse(r#"[{"text": "milk", "left": 5, "top": 6, "width": 50, "height": 300, "tags": ["food", 1]}]"#);
+        let note = &parsed.notes[0];
+        assert_eq!(note.position, Some((5, 6)));
+        // Too narrow to be a note's size, so the default width stays
+        assert_eq!(note.data.width, crate::models::StickerData::new_note(String::new(), String::new()).width);
+        assert_eq!(note.data.height, 300);
+        assert_eq!(note.data.tags, vec!["food".to_string()]);
+    }
+
+    #[test]
+    fn notes_are_found_under_known_root_keys() {
+        for key in ["notes", "items", "stickies", "data"] {
+            let parsed = parse(&format!(r#"{{"{}": [{{"title": "A"}}, {{"body": "B"}}]}}"#, key));
+            assert_eq!(parsed.notes.len(), 2, "root key {}", key);
+        }
+    }
+
+    #[test]
+    fn single_object_is_one_note() {
+        let parsed = parse(r##"{"name": "Groceries", "content": "- milk", "color": "#FFF9C4"}"##);
+        assert_eq!(parsed.notes.len(), 1);
+        assert_eq!(parsed.notes[0].content, "# Groceries\n\n- milk\n");
+    }
+
+    #[test]
+    fn entries_that_are_not_notes_are_reported() {
+        let parsed = parse(r#"[{"title": "Kept"}, {"body": "   "}, "just a string", {"text": "Also kept"}]"#);
+        assert_eq!(parsed.notes.len(), 2);
+        assert_eq!(parsed.skipped, vec!["Entry 2: no title or text".to_string(), "Entry 3: not an object".to_string()]);
+    }
+
+    #[test]
+    fn other_json_is_rejected() {
+        assert!(JsonImporter.parse("42").is_err());
+        assert!(JsonImporter.parse("{not json").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+use crate::models::StickerData;
+
+mod csv;
+mod json;
+mod text;
+
+/// A note read from another sticky-note tool's export
+pub struct ParsedNote {
+    /// Style and geometry for the note. `id` and `file_path` are assigned when the note is created.
+    pub data: StickerData,
+    /// Markdown content of the note
+    pub content: String,
+    /// Absolute position from the source tool, if it stored one
+    pub position: Option<(i32, i32)>,
+}
+
+impl ParsedNote {
+    /// Builds a note from the fields most formats share.
+    /// The title becomes a heading unless the body already starts with it, and colors are mapped onto our palette.
+    pub fn new(title: Option<String>, body: &str, color: Option<&str>) -> Self {
+        let title = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        let body = body.trim();
+
+        let content = match &title {
+            Some(title) if body.is_empty() => format!("# {}\n", title),
+            Some(title) if !body.trim_start_matches('#').trim_start().starts_with(title.as_str()) => {
+                format!("# {}\n\n{}\n", title, body)
+            }
+            _ => format!("{}\n", body),
+        };
+
+        let mut data = StickerData::new_note(String::new(), String::new());
+        data.mode = "preview".to_string();
+        data.title = title;
+        if let Some(hex) = color.and_then(crate::palette::map_to_palette) {
+            data.background_color = hex.to_string();
+        }
+
+        ParsedNote { data, content, position: None }
+    }
+}
+
+/// The notes an importer found in a file, and the entries it left out
+#[derive(Default)]
+pub struct ParsedNotes {
+    pub notes: Vec<ParsedNote>,
+    /// Why each left-out entry is not a note, e.g. "Entry 3: no title or text"
+    pub skipped: Vec<String>,
+}
+
+impl From<Vec<ParsedNote>> for ParsedNotes {
+    fn from(notes: Vec<ParsedNote>) -> Self {
+        ParsedNotes { notes, skipped: Vec::new() }
+    }
+}
+
+/// A parser for one foreign note format
+pub trait NoteImporter: Send + Sync {
+    /// Format id passed to `import_notes` as `format`
+    fn id(&self) -> &'static str;
+    /// File extensions this importer handles, lowercase without the dot
+    fn extensions(&self) -> &'static [&'static str];
+    /// Parses the file content into notes
+    fn parse(&self, content: &str) -> Result<ParsedNotes, String>;
+}
+
+/// All available importers. Add new formats here.
+pub fn importers() -> Vec<Box<dyn NoteImporter>> {
+    vec![
+        Box::new(json::JsonImporter),
+        Box::new(csv::CsvImporter),
+        Box::new(text::TextImporter),
+    ]
+}
+
+/// Finds the importer for an explicit format id, or else by file extension
+pub fn find_importer(path: &Path, format: Option<&str>) -> Option<Box<dyn NoteImporter>> {
+    let extension = path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    importers().into_iter().find(|importer| match format {
+        Some(format) => importer.id() == format,
+        None => importer.extensions().contains(&extension.as_str()),
+    })
+}
+
+/// All extensions supported by some importer, for file dialog filters
+pub fn supported_extensions() -> Vec<&'static str> {
+    importers().iter().flat_map(|importer| importer.extensions().iter().copied()).collect()
+}
@@ -0,0 +1,171 @@
+use super::{NoteImporter, ParsedNote, ParsedNotes};
+
+/// Plain text files, including RTF exported by sticky-note apps (formatting is dropped)
+pub struct TextImporter;
+
+impl NoteImporter for TextImporter {
+    fn id(&self) -> &'static str {
+        "text"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt", "text", "rtf"]
+    }
+
+    fn parse(&self, content: &str) -> Result<ParsedNotes, String> {
+        let text = if content.trim_start().starts_with("{\\rtf") {
+            rtf_to_text(content)
+        } else {
+            content.to_string()
+        };
+
+        if text.trim().is_empty() {
+            return Ok(ParsedNotes::default());
+        }
+        Ok(vec![ParsedNote::new(None, &text, None)].into())
+    }
+}
+
+/// RTF destinations whose contents are metadata rather than text
+const SKIPPED_GROUPS: [&str; 7] = ["fonttbl", "colortbl", "stylesheet", "info", "pict", "header", "footer"];
+
+/// Extracts the text of an RTF document: paragraphs and line breaks are kept,
+/// `\'hh` and `\uN` escapes decoded, and everything else dropped.
+fn rtf_to_text(rtf: &str) -> String {
+    let chars: Vec<char> = rtf.chars().collect();
+    let mut output = String::new();
+    // Group depth at which we started skipping, if inside a skipped group
+    let mut skip_depth: Option<usize> = None;
+    let mut depth = 0usize;
+    // First half of a surrogate pair written as \uN
+    let mut high_surrogate: Option<u32> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '{' => {
+                depth += 1;
+                i += 1;
+            }
+            '}' => {
+                if skip_depth == Some(depth) {
+                    skip_depth = None;
+                }
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            '\\' => {
+                i += 1;
+                let Some(&next) = chars.get(i) else { break };
+
+                if next == '\'' {
+                    // \'hh: a byte in the document code page, treated as Latin-1
+                    let hex: String = chars.iter().skip(i + 1).take(2).collect();
+                    let byte = if hex.len() == 2 { u8::from_str_radix(&hex, 16).ok() } else { None };
+                    if let Some(byte) = byte {
+                        if skip_depth.is_none() {
+                            output.push(byte as char);
+                        }
+                    }
+                    i += 3;
+                    continue;
+                }
+                if !next.is_ascii_alphabetic() {
+                    // Escaped symbol such as \{, \} or \; \* marks an ignorable destination
+                    if next == '*' {
+                        skip_depth.get_or_insert(depth);
+                    } else if skip_depth.is_none() && "{}\\".contains(next) {
+                        output.push(next);
+                    }
+                    i += 1;
+                    continue;
+                }
+
+                let word_start = i;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let word: String = chars[word_start..i].iter().collect();
+                let param_start = i;
+                if i < chars.len() && (chars[i] == '-' || chars[i].is_ascii_digit()) {
+                    i += 1;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+                let param: String = chars[param_start..i].iter().collect();
+                // A single space after a control word is part of it
+                if chars.get(i) == Some(&' ') {
+                    i += 1;
+                }
+
+                if SKIPPED_GROUPS.contains(&word.as_str()) {
+                    skip_depth.get_or_insert(depth);
+                }
+                if skip_depth.is_some() {
+                    continue;
+                }
+
+                match word.as_str() {
+                    "par" | "line" => output.push('\n'),
+                    "tab" => output.push('\t'),
+                    "u" => {
+                        // Characters outside the BMP come as two \uN escapes, a UTF-16 surrogate pair
+                        let unit = param.parse::<i32>().ok().map(|n| (if n < 0 { n + 65536 } else { n }) as u32);
+                        match (high_surrogate.take(), unit) {
+                            (_, Some(high @ 0xD800..=0xDBFF)) => high_surrogate = Some(high),
+                            (Some(high), Some(low @ 0xDC00..=0xDFFF)) => {
+                                output.extend(char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)));
+                            }
+                            (_, Some(unit)) => output.extend(char::from_u32(unit)),
+                            (_, None) => {}
+                        }
+                        // Skip the fallback character that follows \uN
+                        if chars.get(i).is_some_and(|c| *c != '\\' && *c != '{' && *c != '}') {
+                            i += 1;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            '\r' | '\n' => i += 1,
+            c => {
+                if skip_depth.is_none() {
+                    output.push(c);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rtf_text_keeps_paragraphs_and_drops_tables() {
+        let rtf = r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}{\colortbl;\red255\green0\blue0;}\f0 Buy milk\par Call mom\line ok}";
+        assert_eq!(rtf_to_text(rtf), "Buy milk\nCall mom\nok");
+    }
+
+    #[test]
+    fn rtf_escapes_are_decoded() {
+        let rtf = r"{\rtf1 caf\'e9 \u-10179?\u-8704? \{braces\} back\\slash\tab end}";
+        assert_eq!(rtf_to_text(rtf), "café 😀 {braces} back\\slash\tend");
+    }
+
+    #[test]
+    fn rtf_ignorable_destinations_are_skipped() {
+        let rtf = r"{\rtf1{\*\generator Writer;}Text{\*\expandedcolortbl;;} here}";
+        assert_eq!(rtf_to_text(rtf), "Text here");
+    }
+
+    #[test]
+    fn truncated_rtf_does_not_panic() {
+        assert_eq!(rtf_to_text(r"{\rtf1 Half\'e"), "Half");
+        assert_eq!(rtf_to_text("{\\rtf1 Trailing\\"), "Trailing");
+    }
+}
@@ -0,0 +1,335 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use crate::cli::{execute, CliCommand};
+use crate::deep_link::{handle_deep_link, is_deep_link};
+use crate::state::{get_instance_info_path, get_instance_lock_path};
+use crate::window_manager::{create_new_note_backend, is_note_window, show_all_notes};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// A starting instance holds the lock a moment before it can accept connections
+const FORWARD_ATTEMPTS: u32 = 25;
+const FORWARD_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Something a launch asks the app to do, from its command-line arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchAction {
+    OpenFile(String),
+    /// A `peachleaf://` link the OS launched the app with
+    OpenUrl(String),
+    NewNote,
+    ShowAll,
+}
+
+/// How to reach the running instance, written next to the lock file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstanceInfo {
+    pid: u32,
+    port: u16,
+    token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InstanceRequest {
+    Ping,
+    Launch { actions: Vec<LaunchAction> },
+    Cli { command: CliCommand },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InstanceEnvelope {
+    token: String,
+    request: InstanceRequest,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InstanceResponse {
+    pub ok: bool,
+    #[serde(default)]
+    pub output: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// The lock and listener of the instance that owns the data directory
+pub struct PrimaryInstance {
+    // Held (and the lock with it) for as long as the process runs
+    _lock_file: File,
+    listener: TcpListener,
+    token: String,
+}
+
+/// The lock of a command-line run that owns the data directory while it runs
+pub struct HeadlessInstance {
+    _lock_file: File,
+}
+
+pub enum InstanceRole {
+    Primary(PrimaryInstance),
+    /// Another live instance owns the data directory
+    Secondary,
+}
+
+/// Maps launch arguments to actions: `--new-note`, `--show-all`, `peachleaf://` links and markdown file paths.
+/// Relative paths are resolved here, as the running instance may have another working directory.
+pub fn parse_launch_args<I: IntoIterator<Item = String>>(args: I) -> Vec<LaunchAction> {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let mut actions = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--new-note" | "-n" => actions.push(LaunchAction::NewNote),
+            "--show-all" => actions.push(LaunchAction::ShowAll),
+            url if is_deep_link(url) => actions.push(LaunchAction::OpenUrl(url.to_string())),
+            // Unknown flags, e.g. the process serial number macOS passes to apps opened from Finder
+            flag if flag.starts_with('-') => log!("Ignoring launch argument: {}", flag),
+            path => {
+                let path = cwd.join(path);
+                let path = fs::canonicalize(&path).unwrap_or(path);
+                actions.push(LaunchAction::OpenFile(path.to_string_lossy().to_string()));
+            }
+        }
+    }
+
+    actions
+}
+
+/// A hard-to-guess token for authenticating local connections
+pub fn random_token() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    (0..2)
+        .map(|_| {
+            // Each RandomState is seeded from the OS, so this is unpredictable to other processes
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(nanos);
+            hasher.write_u32(std::process::id());
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+fn read_instance_info() -> Option<InstanceInfo> {
+    let json = fs::read_to_string(get_instance_info_path()).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn write_instance_info(info: &InstanceInfo) -> Result<(), String> {
+    let json = serde_json::to_string(info).map_err(|e| e.to_string())?;
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // The token grants control over the app, so only the user may read it
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(get_instance_info_path()).map_err(|e| e.to_string())?;
+    file.write_all(json.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn send_to(info: &InstanceInfo, request: InstanceRequest) -> Result<InstanceResponse, String> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, info.port));
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT)).map_err(|e| e.to_string())?;
+
+    let envelope = InstanceEnvelope { token: info.token.clone(), request };
+    let mut line = serde_json::to_string(&envelope).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response).map_err(|e| e.to_string())?;
+    serde_json::from_str(&response).map_err(|e| format!("Invalid response from running instance: {}", e))
+}
+
+fn become_primary(lock_file: File) -> Result<InstanceRole, String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| format!("Failed to listen for other instances: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let token = random_token();
+
+    write_instance_info(&InstanceInfo { pid: std::process::id(), port, token: token.clone() })?;
+    log!("Acquired instance lock, listening on port {}", port);
+
+    Ok(InstanceRole::Primary(PrimaryInstance { _lock_file: lock_file, listener, token }))
+}
+
+/// Takes the data directory's instance lock. Returns None if another live instance holds it.
+/// The lock is an OS advisory lock, so a crashed instance never leaves a stale one behind.
+fn lock_data_dir() -> Result<Option<File>, String> {
+    let lock_path = get_instance_lock_path();
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let lock_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open lock file: {}", e))?;
+
+    match lock_file.try_lock_exclusive() {
+        Ok(()) => Ok(Some(lock_file)),
+        Err(e) if e.raw_os_error() == fs2::lock_contended_error().raw_os_error() => {
+            log!("Another instance holds the lock");
+            Ok(None)
+        }
+        Err(e) => {
+            // Some network filesystems have no advisory locks: trust the old info only if it answers
+            eprintln!("Failed to lock {:?} ({}), checking for a running instance", lock_path, e);
+            match read_instance_info() {
+                Some(info) if send_to(&info, InstanceRequest::Ping).map(|r| r.ok).unwrap_or(false) => Ok(None),
+                _ => Ok(Some(lock_file)),
+            }
+        }
+    }
+}
+
+/// Takes the data directory's instance lock and listens for later launches, or reports that
+/// another live instance holds it. A crashed instance's instance.json is replaced.
+pub fn acquire() -> Result<InstanceRole, String> {
+    match lock_data_dir()? {
+        Some(lock_file) => {
+            if let Some(previous) = read_instance_info() {
+                log!("Replacing stale instance info left by pid {}", previous.pid);
+            }
+            become_primary(lock_file)
+        }
+        None => Ok(InstanceRole::Secondary),
+    }
+}
+
+/// Takes the instance lock for a command-line run, without listening or touching instance.json:
+/// the run takes no requests, so launches meanwhile must not be sent to it.
+/// Returns None if another live instance holds the lock.
+pub fn acquire_headless() -> Result<Option<HeadlessInstance>, String> {
+    Ok(lock_data_dir()?.map(|lock_file| HeadlessInstance { _lock_file: lock_file }))
+}
+
+/// Sends a request to the running instance, waiting for it to finish starting if needed
+pub fn forward_to_running_instance(request: InstanceRequest) -> Result<InstanceResponse, String> {
+    let mut request = Some(request);
+    let mut last_error = "No running instance found".to_string();
+
+    for _ in 0..FORWARD_ATTEMPTS {
+        if let Some(info) = read_instance_info() {
+            // Ping first so the real request is only sent once, to an instance that answers
+            match send_to(&info, InstanceRequest::Ping) {
+                Ok(_) => return send_to(&info, request.take().unwrap()),
+                Err(e) => last_error = e,
+            }
+        }
+        thread::sleep(FORWARD_RETRY_DELAY);
+    }
+
+    Err(format!("Running instance is not responding: {}", last_error))
+}
+
+/// Carries out launch actions in this instance. A launch without actions brings the notes forward.
+pub fn handle_launch_actions(app: &tauri::AppHandle, actions: Vec<LaunchAction>) {
+    if actions.is_empty() {
+        if let Some(window) = app.webview_windows().values().find(|w| is_note_window(w.label())) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return;
+    }
+
+    for action in actions {
+        println!("Handling launch action: {:?}", action);
+        match action {
+            LaunchAction::NewNote => create_new_note_backend(app),
+            LaunchAction::ShowAll => show_all_notes(app),
+            LaunchAction::OpenUrl(url) => {
+                if let Err(e) = handle_deep_link(app, &url) {
+                    eprintln!("Failed to open {}: {}", url, e);
+                }
+            }
+            // Files opened with the app are edited where they are
+            LaunchAction::OpenFile(path) => {
+                if let Err(e) = crate::commands::file::open_markdown_file(app, &PathBuf::from(&path), Some("link")) {
+                    eprintln!("Failed to open {}: {}", path, e);
+                }
+            }
+        }
+    }
+}
+
+fn handle_request(app: &tauri::AppHandle, request: InstanceRequest) -> InstanceResponse {
+    match request {
+        InstanceRequest::Ping => InstanceResponse { ok: true, ..Default::default() },
+        InstanceRequest::Launch { actions } => {
+            handle_launch_actions(app, actions);
+            InstanceResponse { ok: true, ..Default::default() }
+        }
+        InstanceRequest::Cli { command } => match execute(command, Some(app)) {
+            Ok(output) => InstanceResponse { ok: true, output: Some(output), error: None },
+            Err(e) => InstanceResponse { ok: false, output: None, error: Some(e) },
+        },
+    }
+}
+
+fn handle_connection(app: &tauri::AppHandle, token: &str, stream: TcpStream) -> Result<(), String> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).map_err(|e| e.to_string())?;
+
+    let response = match serde_json::from_str::<InstanceEnvelope>(&line) {
+        Ok(envelope) if envelope.token == token => handle_request(app, envelope.request),
+        Ok(_) => InstanceResponse { ok: false, error: Some("Invalid token".to_string()), ..Default::default() },
+        Err(e) => InstanceResponse { ok: false, error: Some(format!("Invalid request: {}", e)), ..Default::default() },
+    };
+
+    let mut json = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+    json.push('\n');
+    writer.write_all(json.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Accepts requests from later launches for as long as the app runs
+pub fn start_instance_server(app: tauri::AppHandle, instance: PrimaryInstance) {
+    thread::spawn(move || {
+        let PrimaryInstance { _lock_file, listener, token } = instance;
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = handle_connection(&app, &token, stream) {
+                        eprintln!("Failed to handle instance request: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to accept instance connection: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flags_links_and_non_ascii_paths() {
+        let args = ["--new-note", "peachleaf://note/note-1", "/Users/김철수/note.md", "-psn_0_12345"];
+        let actions = parse_launch_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(actions, vec![
+            LaunchAction::NewNote,
+            LaunchAction::OpenUrl("peachleaf://note/note-1".to_string()),
+            LaunchAction::OpenFile("/Users/김철수/note.md".to_string()),
+        ]);
+    }
+}
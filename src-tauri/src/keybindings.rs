@@ -0,0 +1,408 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use muda::accelerator::Accelerator;
+use serde::{Deserialize, Serialize};
+use tauri_plugin_dialog::DialogExt;
+use crate::state::{get_keybindings_file_path, load_config};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A menu item that can have a keyboard shortcut
+pub struct MenuAction {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub default_accelerator: Option<&'static str>,
+}
+
+const fn action(id: &'static str, label: &'static str, default_accelerator: Option<&'static str>) -> MenuAction {
+    MenuAction { id, label, default_accelerator }
+}
+
+/// Every menu item by id, in menu order, with its label and default shortcut
+pub const MENU_ACTIONS: &[MenuAction] = &[
+    action("quit_app", "Quit PeachLeaf", Some("CmdOrCtrl+Q")),
+    action("new_note", "New Note", Some("CmdOrCtrl+N")),
+    action("open_file", "Open File...", Some("CmdOrCtrl+O")),
+    action("close_note", "Close Note", Some("CmdOrCtrl+W")),
+    action("set_reminder", "Set Reminder...", None),
+    action("import_markdown_folder", "Import Markdown Folder...", None),
+    action("import_notes", "Import from Other Apps...", None),
+    action("import_note_bundle", "Import Note Bundle...", None),
+    action("export_note_bundle", "Export Note Bundle...", None),
+    action("export_note", "Export as HTML...", None),
+    action("print", "Print...", Some("CmdOrCtrl+P")),
+    action("use_vault", "Use Markdown Vault...", None),
+    action("use_default_notes", "Use PeachLeaf Notes Folder", None),
+    action("toggle_api", "Enable Local API", None),
+    action("undo", "Undo", Some("CmdOrCtrl+Z")),
+    action("redo", "Redo", Some("CmdOrCtrl+Shift+Z")),
+    action("cut", "Cut", Some("CmdOrCtrl+X")),
+    action("copy", "Copy", Some("CmdOrCtrl+C")),
+    action("paste", "Paste", Some("CmdOrCtrl+V")),
+    action("copy_note_as", "Copy Note As...", None),
+    action("open_color_picker", "Choose Color...", None),
+    action("font_increase", "Increase Font Size", Some("CmdOrCtrl+=")),
+    action("font_decrease", "Decrease Font Size", Some("CmdOrCtrl+-")),
+    action("font_reset", "Reset Font Size", Some("CmdOrCtrl+0")),
+    action("font_family_system", "System", None),
+    action("font_family_serif", "Serif", None),
+    action("font_family_monospace", "Monospace", None),
+    action("custom_font", "Custom Font...", None),
+    action("line_height_tight", "Tight", None),
+    action("line_height_normal", "Normal", None),
+    action("line_height_relaxed", "Relaxed", None),
+    action("line_height_loose", "Loose", None),
+    action("minimize", "Minimize", None),
+    action("zoom", "Zoom", None),
+    action("tile_notes", "Tile Notes", None),
+    action("cascade_notes", "Cascade Notes", None),
+    action("align_left", "Align Left Edges", None),
+    action("align_right", "Align Right Edges", None),
+    action("align_top", "Align Top Edges", None),
+    action("align_bottom", "Align Bottom Edges", None),
+    action("align_center_x", "Align Horizontal Centers", None),
+    action("align_center_y", "Align Vertical Centers", None),
+    action("snap_notes", "Snap While Dragging", None),
+    action("save_layout", "Save Layout for These Monitors", None),
+    action("new_group", "New Group with This Note", None),
+    action("leave_group", "Remove Note from Group", None),
+    action("color_group", "Apply Note's Color to Its Group", None),
+    action("show_all", "Show All Notes", None),
+    action("hide_all", "Hide All Notes", None),
+    action("all_tasks", "All Tasks", Some("CmdOrCtrl+Shift+T")),
+    action("about", "About PeachLeaf", None),
+];
+
+/// Ids of menu items that no longer exist, ignored in keybindings.json written by older versions
+const RETIRED_MENU_IDS: &[&str] = &["font_small", "font_medium", "font_large", "font_xlarge"];
+
+pub fn menu_action(id: &str) -> Option<&'static MenuAction> {
+    MENU_ACTIONS.iter().find(|action| action.id == id)
+}
+
+/// A menu item's effective shortcut, for a cheat sheet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keybinding {
+    pub id: String,
+    pub label: String,
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeybindingsReport {
+    pub bindings: Vec<Keybinding>,
+    /// Invalid, duplicate or unknown entries found in keybindings.json
+    pub problems: Vec<String>,
+}
+
+struct LoadedKeybindings {
+    accelerators: HashMap<&'static str, Option<String>>,
+    problems: Vec<String>,
+    modified: Option<SystemTime>,
+}
+
+// keybindings.json as last loaded
+static KEYBINDINGS: once_cell::sync::Lazy<Mutex<Option<LoadedKeybindings>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+fn parse(accelerator: &str) -> Result<Accelerator, String> {
+    accelerator.parse::<Accelerator>().map_err(|e| e.to_string())
+}
+
+/// Works out every menu item's shortcut from the configured ones (`null` means none).
+/// Unknown ids and invalid combinations are reported and ignored. When two items share
+/// a combination, one the user configured wins over a default; otherwise the first in menu order.
+pub fn resolve_keybindings(
+    configured: &BTreeMap<String, Option<String>>,
+    global_hotkeys: &[String],
+) -> (HashMap<&'static str, Option<String>>, Vec<String>) {
+    let mut problems: Vec<String> = configured
+        .keys()
+        .filter(|id| menu_action(id).is_none() && !RETIRED_MENU_IDS.contains(&id.as_str()))
+        .map(|id| format!("Unknown menu item \"{}\"", id))
+        .collect();
+
+    let wanted: Vec<(&MenuAction, Option<String>, bool)> = MENU_ACTIONS
+        .iter()
+        .map(|action| {
+            let default = action.default_accelerator.map(str::to_string);
+            match configured.get(action.id) {
+                Some(accelerator) => {
+                    let accelerator = accelerator.clone().filter(|a| !a.trim().is_empty());
+                    let custom = accelerator != default;
+                    (action, accelerator, custom)
+                }
+                None => (action, default, false),
+            }
+        })
+        .collect();
+
+    let global: Vec<(Accelerator, &String)> = global_hotkeys
+        .iter()
+        .filter_map(|hotkey| parse(hotkey).ok().map(|parsed| (parsed, hotkey)))
+        .collect();
+
+    let mut accelerators: HashMap<&'static str, Option<String>> = HashMap::new();
+    let mut taken: Vec<(Accelerator, &'static str)> = Vec::new();
+
+    // Custom bindings claim their combinations before defaults do
+    for pass_custom in [true, false] {
+        for (action, accelerator, custom) in &wanted {
+            if *custom != pass_custom {
+                continue;
+            }
+            let Some(accelerator) = accelerator else {
+                accelerators.insert(action.id, None);
+                continue;
+            };
+
+            let parsed = match parse(accelerator) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    problems.push(format!("Invalid shortcut \"{}\" for {}: {}", accelerator, action.label, e));
+                    accelerators.insert(action.id, None);
+                    continue;
+                }
+            };
+
+            if let Some((_, other)) = taken.iter().find(|(used, _)| *used == parsed) {
+                problems.push(format!("\"{}\" for {} is already used for {}", accelerator, action.label, other));
+                accelerators.insert(action.id, None);
+                continue;
+            }
+
+            if let Some((_, hotkey)) = global.iter().find(|(used, _)| *used == parsed) {
+                problems.push(format!(
+                    "\"{}\" for {} is also the global shortcut {}, which takes precedence",
+                    accelerator, action.label, hotkey
+                ));
+            }
+
+            taken.push((parsed, action.label));
+            accelerators.insert(action.id, Some(accelerator.clone()));
+        }
+    }
+
+    (accelerators, problems)
+}
+
+fn default_keybindings() -> BTreeMap<String, Option<String>> {
+    MENU_ACTIONS
+        .iter()
+        .map(|action| (action.id.to_string(), action.default_accelerator.map(str::to_string)))
+        .collect()
+}
+
+fn read_configured() -> (BTreeMap<String, Option<String>>, Vec<String>) {
+    let path = get_keybindings_file_path();
+    if !path.exists() {
+        // Written once so there is something to edit
+        let defaults = default_keybindings();
+        if let Err(e) = write_configured(&defaults) {
+            eprintln!("Failed to write default keybindings: {}", e);
+        }
+        return (defaults, Vec::new());
+    }
+
+    match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|json| {
+        serde_json::from_str::<BTreeMap<String, Option<String>>>(&json).map_err(|e| e.to_string())
+    }) {
+        Ok(configured) => (configured, Vec::new()),
+        Err(e) => (BTreeMap::new(), vec![format!("Failed to read keybindings.json, using defaults: {}", e)]),
+    }
+}
+
+fn write_configured(configured: &BTreeMap<String, Option<String>>) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(configured).map_err(|e| e.to_string())?;
+    let path = get_keybindings_file_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+fn global_hotkeys() -> Vec<String> {
+    let hotkeys = load_config().hotkeys;
+    [hotkeys.new_note, hotkeys.toggle_notes, hotkeys.quick_capture].into_iter().flatten().collect()
+}
+
+fn modified_time() -> Option<SystemTime> {
+    fs::metadata(get_keybindings_file_path()).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Reads keybindings.json and validates it
+fn read_keybindings() -> LoadedKeybindings {
+    let (configured, mut problems) = read_configured();
+    let (accelerators, resolve_problems) = resolve_keybindings(&configured, &global_hotkeys());
+    problems.extend(resolve_problems);
+
+    for problem in &problems {
+        eprintln!("Keybindings: {}", problem);
+    }
+
+    LoadedKeybindings { accelerators, problems, modified: modified_time() }
+}
+
+/// Reads keybindings.json and validates it. Returns the problems found.
+pub fn load_keybindings() -> Vec<String> {
+    let loaded = read_keybindings();
+    let problems = loaded.problems.clone();
+    *KEYBINDINGS.lock().unwrap() = Some(loaded);
+    problems
+}
+
+/// Runs `f` on the loaded keybindings, loading them first if needed, under one lock
+/// so the watcher cannot swap them in between
+fn with_keybindings<T>(f: impl FnOnce(&LoadedKeybindings) -> T) -> T {
+    let mut keybindings = KEYBINDINGS.lock().unwrap();
+    f(keybindings.get_or_insert_with(read_keybindings))
+}
+
+impl LoadedKeybindings {
+    fn accelerator(&self, id: &str) -> Option<String> {
+        match self.accelerators.get(id) {
+            Some(accelerator) => accelerator.clone(),
+            None => menu_action(id).and_then(|action| action.default_accelerator).map(str::to_string),
+        }
+    }
+}
+
+/// A menu item's effective shortcut
+pub fn accelerator_for(id: &str) -> Option<String> {
+    with_keybindings(|loaded| loaded.accelerator(id))
+}
+
+pub fn keybindings_report() -> KeybindingsReport {
+    with_keybindings(|loaded| KeybindingsReport {
+        bindings: MENU_ACTIONS
+            .iter()
+            .map(|action| Keybinding {
+                id: action.id.to_string(),
+                label: action.label.to_string(),
+                accelerator: loaded.accelerator(action.id),
+                default_accelerator: action.default_accelerator.map(str::to_string),
+            })
+            .collect(),
+        problems: loaded.problems.clone(),
+    })
+}
+
+/// Changes one menu item's shortcut in keybindings.json, refusing invalid or already used combinations
+pub fn set_keybinding(id: &str, accelerator: Option<String>) -> Result<(), String> {
+    let action = menu_action(id).ok_or_else(|| format!("Unknown menu item \"{}\"", id))?;
+    let accelerator = accelerator.filter(|a| !a.trim().is_empty());
+    if let Some(accelerator) = &accelerator {
+        parse(accelerator).map_err(|e| format!("Invalid shortcut \"{}\": {}", accelerator, e))?;
+    }
+
+    let (mut configured, _) = read_configured();
+    configured.insert(id.to_string(), accelerator.clone());
+
+    let (accelerators, problems) = resolve_keybindings(&configured, &global_hotkeys());
+    if accelerators.get(action.id).cloned().flatten() != accelerator {
+        return Err(problems
+            .into_iter()
+            .find(|problem| problem.contains(action.label))
+            .unwrap_or_else(|| format!("Could not use this shortcut for {}", action.label)));
+    }
+
+    write_configured(&configured)?;
+    load_keybindings();
+    Ok(())
+}
+
+/// Watches keybindings.json and rebuilds the menu whenever it is edited
+pub fn watch_keybindings(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(WATCH_INTERVAL);
+
+        let last_modified = KEYBINDINGS.lock().unwrap().as_ref().and_then(|loaded| loaded.modified);
+        if modified_time() == last_modified {
+            continue;
+        }
+
+        println!("keybindings.json changed, rebuilding menu");
+        let problems = load_keybindings();
+        crate::menu::rebuild_menu(&app);
+
+        if !problems.is_empty() {
+            app.dialog()
+                .message(format!("Some shortcuts in keybindings.json were not applied:\n\n{}", problems.join("\n")))
+                .title("Keyboard Shortcuts")
+                .show(|_| {});
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configured(entries: &[(&str, Option<&str>)]) -> BTreeMap<String, Option<String>> {
+        entries.iter().map(|(id, accelerator)| (id.to_string(), accelerator.map(str::to_string))).collect()
+    }
+
+    #[test]
+    fn defaults_apply_without_configuration() {
+        let (accelerators, problems) = resolve_keybindings(&BTreeMap::new(), &[]);
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(accelerators["new_note"].as_deref(), Some("CmdOrCtrl+N"));
+        assert_eq!(accelerators["tile_notes"], None);
+    }
+
+    #[test]
+    fn null_removes_a_shortcut() {
+        let (accelerators, problems) = resolve_keybindings(&configured(&[("new_note", None)]), &[]);
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(accelerators["new_note"], None);
+    }
+
+    #[test]
+    fn custom_shortcut_wins_over_a_default() {
+        let (accelerators, problems) = resolve_keybindings(&configured(&[("tile_notes", Some("CmdOrCtrl+N"))]), &[]);
+        assert_eq!(accelerators["tile_notes"].as_deref(), Some("CmdOrCtrl+N"));
+        assert_eq!(accelerators["new_note"], None);
+        assert_eq!(problems, vec!["\"CmdOrCtrl+N\" for New Note is already used for Tile Notes".to_string()]);
+    }
+
+    #[test]
+    fn duplicate_custom_shortcuts_keep_the_first_in_menu_order() {
+        let (accelerators, problems) = resolve_keybindings(
+            &configured(&[("cascade_notes", Some("CmdOrCtrl+Alt+K")), ("tile_notes", Some("CmdOrCtrl+Alt+K"))]),
+            &[],
+        );
+        assert_eq!(accelerators["tile_notes"].as_deref(), Some("CmdOrCtrl+Alt+K"));
+        assert_eq!(accelerators["cascade_notes"], None);
+        assert_eq!(problems.len(), 1);
+    }
+
+    #[test]
+    fn invalid_shortcut_is_reported_and_dropped() {
+        let (accelerators, problems) = resolve_keybindings(&configured(&[("new_note", Some("CmdOrCtrl+Nope"))]), &[]);
+        assert_eq!(accelerators["new_note"], None);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("Invalid shortcut \"CmdOrCtrl+Nope\" for New Note"), "{}", problems[0]);
+    }
+
+    #[test]
+    fn unknown_ids_are_reported_but_retired_ones_are_not() {
+        let (_, problems) = resolve_keybindings(
+            &configured(&[("no_such_item", Some("CmdOrCtrl+J")), ("font_small", Some("CmdOrCtrl+1"))]),
+            &[],
+        );
+        assert_eq!(problems, vec!["Unknown menu item \"no_such_item\"".to_string()]);
+    }
+
+    #[test]
+    fn global_hotkey_clash_is_reported_but_kept() {
+        let (accelerators, problems) = resolve_keybindings(&BTreeMap::new(), &["CmdOrCtrl+N".to_string()]);
+        assert_eq!(accelerators["new_note"].as_deref(), Some("CmdOrCtrl+N"));
+        assert_eq!(problems.len(), 1);
+    }
+}
@@ -0,0 +1,384 @@
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use crate::commands::window::{find_note_data, save_window_state_impl};
+use crate::models::StickerData;
+use crate::state::{get_layouts_file_path, load_app_state};
+use crate::placement::{connected_monitors, find_saved_monitor, restored_position, MonitorGeometry};
+use crate::window_manager::{mark_arranged, move_window};
+
+/// Prefix of the Window menu ids that switch to a layout, followed by its name
+pub const APPLY_LAYOUT_MENU_PREFIX: &str = "apply_layout_";
+/// Prefix of the Window menu ids that delete a layout, followed by its name
+pub const DELETE_LAYOUT_MENU_PREFIX: &str = "delete_layout_";
+
+/// Whether two sets of monitors are the same, in any order: each monitor of one is found in the other,
+/// with the rule notes use to find their monitor, and no monitor is matched twice
+pub fn same_monitors(a: &[MonitorGeometry], b: &[MonitorGeometry]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut unmatched = b.to_vec();
+    a.iter().all(|monitor| {
+        let index = find_saved_monitor(monitor.name.as_deref(), monitor.position, monitor.size, &unmatched)
+            .and_then(|found| unmatched.iter().position(|other| other == found));
+        match index {
+            Some(index) => {
+                unmatched.remove(index);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Where a note was and whether it was shown, in the same units as state.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutNote {
+    pub id: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub monitor_name: Option<String>,
+    #[serde(default)]
+    pub monitor_position: Option<(i32, i32)>,
+    #[serde(default)]
+    pub monitor_size: Option<(u32, u32)>,
+    #[serde(default)]
+    pub monitor_scale_factor: Option<f64>,
+}
+
+impl LayoutNote {
+    fn capture(note: &StickerData) -> Self {
+        LayoutNote {
+            id: note.id.clone(),
+            x: note.x,
+            y: note.y,
+            width: note.width,
+            height: note.height,
+            hidden: note.hidden,
+            monitor_name: note.monitor_name.clone(),
+            monitor_position: note.monitor_position,
+            monitor_size: note.monitor_size,
+            monitor_scale_factor: note.monitor_scale_factor,
+        }
+    }
+
+    /// The note with this layout's geometry, visibility and monitor
+    fn place(&self, note: StickerData) -> StickerData {
+        StickerData {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            hidden: self.hidden,
+            monitor_name: self.monitor_name.clone(),
+            monitor_position: self.monitor_position,
+            monitor_size: self.monitor_size,
+            monitor_scale_factor: self.monitor_scale_factor,
+            ..note
+        }
+    }
+}
+
+/// A named arrangement of the notes, saved for the monitors connected at the time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Layout {
+    pub name: String,
+    pub monitors: Vec<MonitorGeometry>,
+    pub notes: Vec<LayoutNote>,
+    /// Milliseconds since the Unix epoch
+    pub saved_at: u64,
+}
+
+impl Layout {
+    /// The note placed as in this layout; notes created after the layout was saved are unchanged
+    pub fn place(&self, note: StickerData) -> StickerData {
+        match self.notes.iter().find(|saved| saved.id == note.id) {
+            Some(saved) => saved.place(note),
+            None => note,
+        }
+    }
+}
+
+/// layouts.json, stored beside state.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LayoutsFile {
+    pub layouts: Vec<Layout>,
+    /// The layout that was last saved or switched to
+    pub active: Option<String>,
+    /// Monitors connected when layouts were last checked, to notice when they change
+    pub last_monitors: Vec<MonitorGeometry>,
+}
+
+/// A layout as listed by the list_layouts command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutSummary {
+    pub name: String,
+    pub monitors: Vec<MonitorGeometry>,
+    pub note_count: usize,
+    pub saved_at: u64,
+    pub active: bool,
+    /// Saved with the monitors that are connected now
+    pub matches_monitors: bool,
+}
+
+pub fn load_layouts() -> LayoutsFile {
+    match fs::read_to_string(get_layouts_file_path()) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            eprintln!("Failed to parse layouts JSON: {}", e);
+            LayoutsFile::default()
+        }),
+        Err(_) => LayoutsFile::default(),
+    }
+}
+
+fn save_layouts(layouts: &LayoutsFile) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(layouts).map_err(|e| e.to_string())?;
+    let layouts_path = get_layouts_file_path();
+    if let Some(parent) = layouts_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&layouts_path, json).map_err(|e| e.to_string())?;
+    println!("Layouts saved to: {:?}", layouts_path);
+    Ok(())
+}
+
+/// Default name of a layout: its monitors' names, e.g. "Built-in Display + DELL U2720Q"
+pub fn monitors_layout_name(monitors: &[MonitorGeometry]) -> String {
+    let names: Vec<String> = monitors
+        .iter()
+        .map(|monitor| monitor.name.clone().unwrap_or_else(|| format!("{}×{}", monitor.size.0, monitor.size.1)))
+        .collect();
+    if names.is_empty() {
+        "Layout".to_string()
+    } else {
+        names.join(" + ")
+    }
+}
+
+pub fn list_layouts(app: &tauri::AppHandle) -> Vec<LayoutSummary> {
+    let monitors = connected_monitors(app);
+    let layouts = load_layouts();
+    layouts
+        .layouts
+        .iter()
+        .map(|layout| LayoutSummary {
+            name: layout.name.clone(),
+            monitors: layout.monitors.clone(),
+            note_count: layout.notes.len(),
+            saved_at: layout.saved_at,
+            active: layouts.active.as_deref() == Some(layout.name.as_str()),
+            matches_monitors: same_monitors(&layout.monitors, &monitors),
+        })
+        .collect()
+}
+
+/// Saves every note's position, size, visibility and monitor as a layout for the connected monitors.
+/// Without a name the layout is named after the monitors; an existing layout with the name is replaced.
+pub fn save_layout(app: &tauri::AppHandle, name: Option<&str>) -> Result<Layout, String> {
+    // Positions and visibility of open windows are only in memory until saved
+    save_window_state_impl(app)?;
+
+    let monitors = connected_monitors(app);
+    let name = name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| monitors_layout_name(&monitors));
+    let saved_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0);
+    let layout = Layout {
+        name: name.clone(),
+        monitors: monitors.clone(),
+        notes: load_app_state()?.windows.iter().map(LayoutNote::capture).collect(),
+        saved_at,
+    };
+
+    let mut layouts = load_layouts();
+    match layouts.layouts.iter_mut().find(|saved| saved.name == name) {
+        Some(saved) => *saved = layout.clone(),
+        None => layouts.layouts.push(layout.clone()),
+    }
+    layouts.active = Some(name);
+    layouts.last_monitors = monitors;
+    save_layouts(&layouts)?;
+
+    println!("Saved layout {:?} with {} notes", layout.name, layout.notes.len());
+    crate::menu::rebuild_menu(app);
+    Ok(layout)
+}
+
+/// Moves the open notes to where a layout puts them and shows or hides them.
+/// Notes on a monitor that is not connected go to the primary monitor, as on launch.
+/// Returns how many notes were placed.
+pub fn apply_layout(app: &tauri::AppHandle, name: &str) -> Result<usize, String> {
+    let mut layouts = load_layouts();
+    let layout = layouts
+        .layouts
+        .iter()
+        .find(|layout| layout.name == name)
+        .cloned()
+        .ok_or_else(|| format!("Layout not found: {}", name))?;
+
+    let monitors = connected_monitors(app);
+    let mut placed = 0;
+    for saved in &layout.notes {
+        let (Some(window), Some(note)) = (app.get_webview_window(&saved.id), find_note_data(&saved.id)) else {
+            continue;
+        };
+        let note = saved.place(note);
+        let placement = restored_position(&note, &monitors);
+
+        // Placing the window is not a drag, so don't snap it
+        mark_arranged(&note.id);
+        move_window(&window, &placement, note.width, note.height)?;
+        if note.hidden {
+            window.hide().map_err(|e| e.to_string())?;
+        } else {
+            window.show().map_err(|e| e.to_string())?;
+        }
+        placed += 1;
+    }
+
+    layouts.active = Some(layout.name.clone());
+    layouts.last_monitors = monitors;
+    save_layouts(&layouts)?;
+    save_window_state_impl(app)?;
+    crate::commands::window::refresh_note_lists(app);
+
+    println!("Applied layout {:?} to {} notes", layout.name, placed);
+    crate::menu::rebuild_menu(app);
+    Ok(placed)
+}
+
+pub fn delete_layout(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    let mut layouts = load_layouts();
+    let count = layouts.layouts.len();
+    layouts.layouts.retain(|layout| layout.name != name);
+    if layouts.layouts.len() == count {
+        return Err(format!("Layout not found: {}", name));
+    }
+    if layouts.active.as_deref() == Some(name) {
+        layouts.active = None;
+    }
+    save_layouts(&layouts)?;
+
+    println!("Deleted layout {:?}", name);
+    crate::menu::rebuild_menu(app);
+    Ok(())
+}
+
+/// If the connected monitors changed since layouts were last checked, picks the layout saved
+/// for these monitors (the active one, else the newest) and records the monitors.
+/// The caller places the notes with `Layout::place`.
+pub fn select_layout_for_monitors(app: &tauri::AppHandle) -> Option<Layout> {
+    let monitors = connected_monitors(app);
+    let mut layouts = load_layouts();
+    if same_monitors(&layouts.last_monitors, &monitors) {
+        return None;
+    }
+
+    let selected = layouts
+        .layouts
+        .iter()
+        .filter(|layout| same_monitors(&layout.monitors, &monitors))
+        .max_by_key(|layout| (layouts.active.as_deref() == Some(layout.name.as_str()), layout.saved_at))
+        .cloned();
+
+    match &selected {
+        Some(layout) => println!("Monitors changed, switching to layout {:?}", layout.name),
+        None => println!("Monitors changed, no layout saved for them"),
+    }
+    layouts.active = selected.as_ref().map(|layout| layout.name.clone());
+    layouts.last_monitors = monitors;
+    if let Err(e) = save_layouts(&layouts) {
+        eprintln!("Failed to save layouts: {}", e);
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: Option<&str>, x: i32, width: u32) -> MonitorGeometry {
+        MonitorGeometry {
+            name: name.map(str::to_string),
+            position: (x, 0),
+            size: (width, 1440),
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn monitors_match_in_any_order() {
+        let saved = [monitor(Some("Built-in Display"), 0, 2560), monitor(None, 2560, 1920)];
+        let connected = [monitor(None, 2560, 1920), monitor(Some("Built-in Display"), 0, 2560)];
+        assert!(same_monitors(&saved, &connected));
+        assert!(!same_monitors(&saved, &connected[..1]));
+    }
+
+    #[test]
+    fn monitors_match_by_name_after_moving() {
+        let saved = [monitor(Some("DELL U2720Q"), 2560, 3840)];
+        let connected = [monitor(Some("DELL U2720Q"), -3840, 3840)];
+        assert!(same_monitors(&saved, &connected));
+    }
+
+    #[test]
+    fn each_monitor_matches_only_once() {
+        // Two monitors of the same model share a name; one of them is not a match for a different monitor
+        let saved = [monitor(Some("DELL U2720Q"), 0, 3840), monitor(Some("DELL U2720Q"), 3840, 3840)];
+        let connected = [monitor(Some("DELL U2720Q"), 0, 3840), monitor(Some("Built-in Display"), -2560, 2560)];
+        assert!(!same_monitors(&saved, &connected));
+        assert!(!same_monitors(&connected, &saved));
+    }
+
+    #[test]
+    fn layouts_saved_without_scale_factors_still_load() {
+        let json = r#"{"layouts":[{"name":"Desk","monitors":[{"name":null,"position":[0,0],"size":[1920,1080]}],"notes":[],"savedAt":1}],"lastMonitors":[]}"#;
+        let layouts: LayoutsFile = serde_json::from_str(json).unwrap();
+        assert_eq!(layouts.layouts[0].monitors[0].scale_factor, 1.0);
+        assert_eq!(monitors_layout_name(&layouts.layouts[0].monitors), "1920×1080");
+    }
+
+    #[test]
+    fn layout_places_only_its_own_notes() {
+        let layout = Layout {
+            name: "Desk".to_string(),
+            monitors: Vec::new(),
+            notes: vec![LayoutNote {
+                id: "note-1".to_string(),
+                x: 10,
+                y: 20,
+                width: 300,
+                height: 200,
+                hidden: true,
+                monitor_name: None,
+                monitor_position: None,
+                monitor_size: None,
+                monitor_scale_factor: None,
+            }],
+            saved_at: 1,
+        };
+
+        let placed = layout.place(StickerData::new_note("note-1".to_string(), "note-1.md".to_string()));
+        assert_eq!((placed.x, placed.y, placed.width, placed.height, placed.hidden), (10, 20, 300, 200, true));
+
+        let other = StickerData::new_note("note-2".to_string(), "note-2.md".to_string());
+        let unchanged = layout.place(other.clone());
+        assert_eq!((unchanged.x, unchanged.y, unchanged.hidden), (other.x, other.y, other.hidden));
+    }
+}
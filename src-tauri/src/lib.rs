@@ -1,25 +1,86 @@
 use tauri::{Manager, RunEvent};
 
+/// `println!` for log lines on paths the command-line subcommands share with the app.
+/// While a subcommand runs they go to stderr instead, so its stdout only carries the command's output.
+macro_rules! log {
+    ($($arg:tt)*) => {
+        if $crate::cli::is_running() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
 // Module declarations
 mod models;
 mod state;
 mod commands;
 mod menu;
 mod window_manager;
+mod thumbnail;
+mod clipboard;
+mod markdown;
+mod palette;
+mod fonts;
+mod arrange;
+mod placement;
+mod layouts;
+mod groups;
+mod reminders;
+mod tasks;
+mod monitors;
+mod importers;
+mod vault;
+mod instance;
+mod api;
+mod deep_link;
+mod tray;
+mod hotkeys;
+mod keybindings;
+pub mod cli;
 
 // Re-export for external use if needed
 pub use models::{StickerData, AppState};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let launch_actions = instance::parse_launch_args(std::env::args().skip(1));
+
+    // Only one instance may own the data directory; later launches hand their arguments over and exit
+    let primary_instance = match instance::acquire() {
+        Ok(instance::InstanceRole::Primary(primary)) => Some(primary),
+        Ok(instance::InstanceRole::Secondary) => {
+            let request = instance::InstanceRequest::Launch { actions: launch_actions };
+            match instance::forward_to_running_instance(request) {
+                Ok(response) if response.ok => {
+                    println!("Launch forwarded to the running instance");
+                    return;
+                }
+                Ok(response) => eprintln!("Running instance rejected launch: {:?}", response.error),
+                Err(e) => eprintln!("Failed to forward launch: {}", e),
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to acquire instance lock, continuing without it: {}", e);
+            None
+        }
+    };
+
     tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
         .invoke_handler(tauri::generate_handler![
             commands::file::read_file,
             commands::file::write_file,
             commands::file::select_file,
             commands::file::delete_note_file,
+            commands::file::set_note_content,
+            commands::file::append_to_note,
             commands::window::create_sticker_window,
             commands::color::open_color_picker,
             commands::color::close_color_picker,
@@ -29,15 +90,65 @@ pub fn run() {
             commands::window::get_window_data,
             commands::window::update_window_metadata,
             commands::window::on_window_focus,
+            commands::window::list_notes,
+            commands::window::create_note,
+            commands::window::focus_note,
+            commands::window::hide_note,
+            commands::window::delete_note,
+            commands::window::set_note_color,
+            commands::window::set_note_font,
+            commands::window::tile_notes,
+            commands::window::cascade_notes,
+            commands::window::align_notes,
             commands::image::save_pasted_image,
             commands::image::delete_image,
             commands::image::cleanup_note_images,
             commands::image::read_clipboard_image,
-            commands::image::read_image_as_data_url
+            commands::image::read_image_as_data_url,
+            commands::clipboard::copy_note_to_clipboard,
+            commands::clipboard::render_note_for_clipboard,
+            commands::export::export_note,
+            commands::bundle::export_note_bundle,
+            commands::bundle::import_note_bundle,
+            commands::import::import_markdown_folder,
+            commands::import::import_notes,
+            commands::vault::get_vault_config,
+            commands::vault::set_vault_folder,
+            commands::vault::use_default_notes_folder,
+            commands::api::get_api_config,
+            commands::api::set_api_enabled,
+            commands::hotkeys::get_hotkeys,
+            commands::hotkeys::set_hotkey,
+            commands::hotkeys::append_to_inbox,
+            commands::hotkeys::close_quick_capture,
+            commands::keybindings::get_keybindings,
+            commands::keybindings::set_keybinding,
+            commands::layouts::list_layouts,
+            commands::layouts::save_layout,
+            commands::layouts::apply_layout,
+            commands::layouts::delete_layout,
+            commands::groups::list_groups,
+            commands::groups::create_group,
+            commands::groups::rename_group,
+            commands::groups::delete_group,
+            commands::groups::set_note_group,
+            commands::groups::show_group,
+            commands::groups::hide_group,
+            commands::groups::set_group_color,
+            commands::reminders::list_reminders,
+            commands::reminders::set_reminder,
+            commands::reminders::clear_reminder,
+            commands::reminders::dismiss_reminder,
+            commands::tasks::list_tasks,
+            commands::tasks::toggle_task,
+            commands::tasks::open_tasks_window
         ])
         .setup(|app| {
-            // Create menu
-            let menu = menu::create_menu(app)?;
+            // Create menu, with shortcuts from keybindings.json
+            keybindings::load_keybindings();
+            // Notes are placed by the layout saved for the connected monitors, if they changed since the last run
+            let layout = layouts::select_layout_for_monitors(app.handle());
+            let menu = menu::create_menu(app.handle())?;
             app.set_menu(menu)?;
 
             // Restore saved windows
@@ -57,6 +168,10 @@ pub fn run() {
                     } else {
                         println!("Restoring {} saved windows", state.windows.len());
                         for window_data in state.windows {
+                            let window_data = match &layout {
+                                Some(layout) => layout.place(window_data),
+                                None => window_data,
+                            };
                             window_manager::restore_window(&app_handle, window_data);
                         }
                     }
@@ -69,8 +184,66 @@ pub fn run() {
                 }
             }
 
-            // Setup menu event handler
+            // Setup menu event handler, and rebuild the menu when keybindings.json is edited
             menu::setup_menu_handler(&app_handle);
+            keybindings::watch_keybindings(app_handle.clone());
+
+            // Rescue notes from monitors that are unplugged while running, and return them later
+            monitors::watch_monitors(app_handle.clone());
+
+            // Notify about due reminders, and about the ones missed while PeachLeaf was closed
+            reminders::watch_reminders(app_handle.clone());
+
+            // Tray icon and Window menu with the restored notes; their items go through the same handler
+            let notes: Vec<_> = {
+                let metadata = commands::window::WINDOW_METADATA.lock().unwrap();
+                let mut notes: Vec<_> = metadata.values().map(commands::window::note_summary).collect();
+                notes.sort_by(|a, b| a.id.cmp(&b.id));
+                notes
+            };
+            if let Err(e) = tray::create_tray(&app_handle, &notes) {
+                eprintln!("Failed to create tray icon: {}", e);
+            }
+            menu::update_window_menu_notes(&app_handle, &notes);
+
+            // Accept launches forwarded by later instances, then handle this launch's own arguments
+            if let Some(primary) = primary_instance {
+                instance::start_instance_server(app_handle.clone(), primary);
+            }
+            if !launch_actions.is_empty() {
+                instance::handle_launch_actions(&app_handle, launch_actions);
+            }
+
+            // On Linux and Windows a peachleaf:// link launches the app with the link as an argument,
+            // which reaches the running instance like any other launch; macOS sends it to this process
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+
+                #[cfg(any(target_os = "linux", windows))]
+                if let Err(e) = app.deep_link().register_all() {
+                    eprintln!("Failed to register {}:// links: {}", deep_link::URL_SCHEME, e);
+                }
+
+                let link_handle = app_handle.clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        if let Err(e) = deep_link::handle_deep_link(&link_handle, url.as_str()) {
+                            eprintln!("Failed to open {}: {}", url, e);
+                        }
+                    }
+                });
+            }
+
+            // System-wide shortcuts; ones that are invalid or taken by other apps are skipped
+            hotkeys::register_hotkeys(&app_handle, &state::load_config().hotkeys);
+
+            // The local API only runs when the user turned it on
+            let api_config = state::load_config().api;
+            if api_config.enabled {
+                if let Err(e) = api::start_api_server(&app_handle, &api_config) {
+                    eprintln!("{}", e);
+                }
+            }
 
             Ok(())
         })
@@ -82,15 +255,19 @@ pub fn run() {
                 RunEvent::ExitRequested { api, .. } => {
                     api.prevent_exit();
                 }
-                // Save state when a window is destroyed
+                // Save state when a window is destroyed, snap notes the user dragged
                 RunEvent::WindowEvent { label, event: window_event, .. } => {
+                    if let tauri::WindowEvent::Moved(_) = window_event {
+                        window_manager::note_moved(app_handle, &label);
+                    }
                     if let tauri::WindowEvent::Destroyed = window_event {
                         println!("Window {} was destroyed, saving state...", label);
                         let app = app_handle.clone();
                         tauri::async_runtime::spawn(async move {
-                            if let Err(e) = commands::window::save_window_state(app).await {
+                            if let Err(e) = commands::window::save_window_state(app.clone()).await {
                                 eprintln!("Failed to save window state after window close: {}", e);
                             }
+                            commands::window::refresh_note_lists(&app);
                         });
                     }
                 }
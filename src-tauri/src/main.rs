@@ -2,5 +2,15 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
-  app_lib::run();
+  let args: Vec<String> = std::env::args().skip(1).collect();
+
+  // Subcommands (new, list, show, append) run as a command-line tool instead of opening the app
+  match app_lib::cli::parse_cli_args(&args) {
+    Ok(Some(command)) => std::process::exit(app_lib::cli::run_cli(command)),
+    Ok(None) => app_lib::run(),
+    Err(e) => {
+      eprintln!("peach-leaf: {}\n\n{}", e, app_lib::cli::USAGE);
+      std::process::exit(2);
+    }
+  }
 }
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use base64::{Engine as _, engine::general_purpose};
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use crate::commands::image::image_mime_type;
+use crate::models::StickerData;
+
+/// Escape text for use inside HTML attributes and text nodes
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Collects `![alt](src)<!-- width:N -->` hints written by the editor's image resize handles.
+/// Like the preview, the first hint found for a path wins.
+pub fn image_width_hints(markdown: &str) -> HashMap<String, u32> {
+    let mut hints = HashMap::new();
+    let mut search_from = 0;
+
+    while let Some(offset) = markdown[search_from..].find(")<!--") {
+        let close_paren = search_from + offset;
+        search_from = close_paren + 1;
+
+        let Some(open_paren) = markdown[..close_paren].rfind("](") else {
+            continue;
+        };
+        let src = &markdown[open_paren + 2..close_paren];
+
+        let comment = &markdown[close_paren + ")<!--".len()..];
+        let Some(comment_end) = comment.find("-->") else {
+            continue;
+        };
+        let hint = comment[..comment_end].trim();
+        if let Some(width) = hint.strip_prefix("width:").and_then(|w| w.trim().parse::<u32>().ok()) {
+            hints.entry(src.to_string()).or_insert(width);
+        }
+    }
+
+    hints
+}
+
+/// Reads an image referenced relative to the note and returns it as a data URL
+fn inline_image(note_dir: &Path, src: &str) -> Option<String> {
+    let relative = src.strip_prefix("./").unwrap_or(src);
+    let image_path = note_dir.join(relative);
+    match fs::read(&image_path) {
+        Ok(bytes) => Some(format!(
+            "data:{};base64,{}",
+            image_mime_type(&image_path),
+            general_purpose::STANDARD.encode(&bytes)
+        )),
+        Err(e) => {
+            eprintln!("Failed to inline image {:?}: {}", image_path, e);
+            None
+        }
+    }
+}
+
+/// Render note markdown to an HTML fragment with local images inlined as data URLs.
+/// Mirrors the preview: GFM tables, strikethrough and task lists, single newlines as line breaks,
+/// and `<!-- width:N -->` image hints.
+pub fn render_markdown_html(markdown: &str, note_path: &Path) -> String {
+    let note_dir = note_path.parent().unwrap_or_else(|| Path::new("."));
+    let width_hints = image_width_hints(markdown);
+
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut events = Vec::new();
+    // (src, title, alt text) of the image currently being parsed
+    let mut current_image: Option<(String, String, String)> = None;
+
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Image { dest_url, title, .. }) => {
+                current_image = Some((dest_url.to_string(), title.to_string(), String::new()));
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((src, title, alt)) = current_image.take() {
+                    let resolved_src = if src.starts_with("./") || src.starts_with("../") {
+                        inline_image(note_dir, &src).unwrap_or(src.clone())
+                    } else {
+                        src.clone()
+                    };
+                    let width_style = width_hints
+                        .get(&src)
+                        .map(|width| format!(" style=\"width: {}px; height: auto;\"", width))
+                        .unwrap_or_default();
+                    events.push(Event::Html(
+                        format!(
+                            "<img src=\"{}\" alt=\"{}\" title=\"{}\"{} />",
+                            escape_html(&resolved_src),
+                            escape_html(&alt),
+                            escape_html(&title),
+                            width_style
+                        )
+                        .into(),
+                    ));
+                }
+            }
+            Event::Text(text) | Event::Code(text) if current_image.is_some() => {
+                if let Some((_, _, alt)) = current_image.as_mut() {
+                    alt.push_str(&text);
+                }
+            }
+            _ if current_image.is_some() => {}
+            // Match marked's `breaks: true`
+            Event::SoftBreak => events.push(Event::HardBreak),
+            other => events.push(other),
+        }
+    }
+
+    let mut html_output = String::new();
+    html::push_html(&mut html_output, events.into_iter());
+    html_output
+}
+
+/// Render a note as an HTML fragment styled with its colors and font size,
+/// suitable for pasting into rich text editors
+pub fn render_note_fragment(markdown: &str, note: &StickerData) -> String {
+    format!(
+        "<div style=\"background-color: {}; color: {}; font-size: {}px; line-height: 1.6; padding: 12px;\">{}</div>",
+        escape_html(&note.background_color),
+        escape_html(&note.text_color),
+        note.font_size,
+        render_markdown_html(markdown, Path::new(&note.file_path))
+    )
+}
+
+/// Title of a note: its first non-empty line without heading markers
+pub fn note_title(markdown: &str) -> Option<String> {
+    markdown
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .map(|line| line.chars().take(80).collect())
+}
+
+/// Styles mirroring MarkdownPreview.svelte so exported notes look like the preview
+const DOCUMENT_STYLE: &str = r#"
+  body { margin: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Oxygen, Ubuntu, Cantarell, sans-serif; }
+  .note { max-width: 800px; margin: 0 auto; padding: 24px; line-height: 1.6; min-height: 100vh; box-sizing: border-box; }
+  h1 { font-size: 1.8em; margin: 0; padding: 0; }
+  h2 { font-size: 1.5em; margin: 0; padding: 0; }
+  h3 { font-size: 1.3em; margin: 0; padding: 0; }
+  h4 { font-size: 1.1em; margin: 0; padding: 0; }
+  h5 { font-size: 1.0em; margin: 0; padding: 0; }
+  h6 { font-size: 0.9em; margin: 0; padding: 0; }
+  code { background: rgba(0, 0, 0, 0.05); padding: 2px 6px; border-radius: 3px; font-family: Monaco, Menlo, monospace; font-size: 0.9em; }
+  pre { background: rgba(0, 0, 0, 0.05); padding: 12px; border-radius: 4px; overflow-x: auto; }
+  pre code { background: none; padding: 0; }
+  blockquote { border-left: 4px solid rgba(0, 0, 0, 0.2); padding-left: 12px; margin-left: 0; color: rgba(0, 0, 0, 0.6); }
+  a { color: #0066cc; text-decoration: none; }
+  a:hover { text-decoration: underline; }
+  ul, ol { padding-left: 24px; }
+  table { border-collapse: collapse; width: 100%; margin: 1em 0; }
+  th, td { border: 1px solid rgba(0, 0, 0, 0.2); padding: 6px 12px; text-align: left; }
+  th { background: rgba(0, 0, 0, 0.05); font-weight: bold; }
+  img { max-width: 100%; height: auto; }
+"#;
+
+/// Render a note as a self-contained HTML document: images are inlined and the page
+/// uses the note's background color, text color and font size
+pub fn render_note_document(markdown: &str, note: &StickerData) -> String {
+    let title = note_title(markdown).unwrap_or_else(|| note.id.clone());
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}  body {{ background-color: {}; color: {}; font-size: {}px; }}\n</style>\n</head>\n<body>\n<div class=\"note\">\n{}</div>\n</body>\n</html>\n",
+        escape_html(&title),
+        DOCUMENT_STYLE,
+        escape_html(&note.background_color),
+        escape_html(&note.text_color),
+        note.font_size,
+        render_markdown_html(markdown, Path::new(&note.file_path))
+    )
+}
+
+/// Fields read from a YAML front matter block at the top of an imported note
+#[derive(Debug, Default, Clone)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub color: Option<String>,
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        value[1..value.len() - 1].to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parses `tags: [a, b]`, `tags: a, b` and `tags: #a #b` values
+fn parse_inline_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let items: Vec<&str> = if value.starts_with('[') || value.contains(',') {
+        value.trim_start_matches('[').trim_end_matches(']').split(',').collect()
+    } else {
+        value.split_whitespace().collect()
+    };
+    items
+        .into_iter()
+        .map(|item| unquote(item).trim_start_matches('#').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Splits a leading `---` front matter block from the note body.
+/// Only the simple `key: value` and list forms used by common markdown tools are understood;
+/// anything else is ignored.
+pub fn split_front_matter(markdown: &str) -> (Option<FrontMatter>, &str) {
+    let Some(rest) = markdown.strip_prefix("---\n").or_else(|| markdown.strip_prefix("---\r\n")) else {
+        return (None, markdown);
+    };
+
+    // Find the closing delimiter on its own line
+    let mut offset = 0;
+    let mut block_end = None;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            block_end = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((block_len, body_start)) = block_end else {
+        return (None, markdown);
+    };
+
+    let mut front_matter = FrontMatter::default();
+    let mut current_list_key: Option<String> = None;
+
+    for line in rest[..block_len].lines() {
+        // Continuation of a block list: "  - item"
+        if let Some(item) = line.trim_start().strip_prefix("- ") {
+            if current_list_key.as_deref() == Some("tags") {
+                let tag = unquote(item).trim_start_matches('#').to_string();
+                if !tag.is_empty() {
+                    front_matter.tags.push(tag);
+                }
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        current_list_key = if value.is_empty() { Some(key.clone()) } else { None };
+
+        match key.as_str() {
+            "title" if !value.is_empty() => front_matter.title = Some(unquote(value)),
+            "tags" | "tag" | "keywords" if !value.is_empty() => front_matter.tags.extend(parse_inline_list(value)),
+            "tags" | "tag" | "keywords" => current_list_key = Some("tags".to_string()),
+            "color" | "colour" | "background" | "background_color" | "backgroundcolor" if !value.is_empty() => {
+                front_matter.color = Some(unquote(value))
+            }
+            _ => {}
+        }
+    }
+
+    let body = rest[body_start..].trim_start_matches(['\r', '\n']);
+    (Some(front_matter), body)
+}
+
+/// Rewrites the destination of every markdown image.
+/// `rewrite` receives the raw destination and returns a replacement, or None to leave it unchanged.
+/// Images inside code blocks are not touched.
+pub fn rewrite_image_sources(markdown: &str, mut rewrite: impl FnMut(&str) -> Option<String>) -> String {
+    let mut replacements = Vec::new();
+
+    for (event, range) in Parser::new_ext(markdown, Options::empty()).into_offset_iter() {
+        if let Event::Start(Tag::Image { .. }) = event {
+            let image_source = &markdown[range.clone()];
+            // Inline images end with `](destination "title")`
+            let Some(dest_offset) = image_source.rfind("](") else {
+                continue;
+            };
+            let dest_start = range.start + dest_offset + 2;
+            let dest_text = &markdown[dest_start..range.end];
+
+            let (raw_start, raw_len) = if let Some(bracketed) = dest_text.strip_prefix('<') {
+                (dest_start + 1, bracketed.find('>').unwrap_or(0))
+            } else {
+                (dest_start, dest_text.find(|c: char| c == ')' || c.is_whitespace()).unwrap_or(0))
+            };
+            if raw_len == 0 {
+                continue;
+            }
+
+            let raw = &markdown[raw_start..raw_start + raw_len];
+            if let Some(new_dest) = rewrite(raw) {
+                replacements.push((raw_start, raw_start + raw_len, new_dest));
+            }
+        }
+    }
+
+    let mut output = String::with_capacity(markdown.len());
+    let mut last = 0;
+    for (start, end, new_dest) in replacements {
+        output.push_str(&markdown[last..start]);
+        output.push_str(&new_dest);
+        last = end;
+    }
+    output.push_str(&markdown[last..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn front_matter_fields_are_read() {
+        let markdown = "---\ntitle: \"Groceries\"\ntags: [food, '#weekly']\ncolor: pink\n---\n\n- milk\n";
+        let (front_matter, body) = split_front_matter(markdown);
+        let front_matter = front_matter.unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Groceries"));
+        assert_eq!(front_matter.tags, vec!["food", "weekly"]);
+        assert_eq!(front_matter.color.as_deref(), Some("pink"));
+        assert_eq!(body, "- milk\n");
+    }
+
+    #[test]
+    fn front_matter_block_lists_and_crlf() {
+        let markdown = "---\r\ntags:\r\n  - a\r\n  - \"#b\"\r\nother: x\r\n...\r\nBody";
+        let (front_matter, body) = split_front_matter(markdown);
+        assert_eq!(front_matter.unwrap().tags, vec!["a", "b"]);
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn missing_or_unterminated_front_matter_keeps_the_note() {
+        let plain = "# Title\n---\ntext";
+        let (front_matter, body) = split_front_matter(plain);
+        assert!(front_matter.is_none());
+        assert_eq!(body, plain);
+
+        let unterminated = "---\ntitle: Never closed\n\nThe rest of the note";
+        let (front_matter, body) = split_front_matter(unterminated);
+        assert!(front_matter.is_none());
+        assert_eq!(body, unterminated);
+    }
+
+    #[test]
+    fn image_sources_are_rewritten_outside_code() {
+        let markdown = "![a](img/a.png) ![b](<img/my b.png> \"Title\")\n\n```\n![c](img/c.png)\n```\n";
+        let rewritten = rewrite_image_sources(markdown, |src| Some(format!("./note_images/{}", src.trim_start_matches("img/"))));
+        assert_eq!(
+            rewritten,
+            "![a](./note_images/a.png) ![b](<./note_images/my b.png> \"Title\")\n\n```\n![c](img/c.png)\n```\n"
+        );
+    }
+
+    #[test]
+    fn image_sources_left_alone_when_not_rewritten() {
+        let markdown = "![remote](https://example.com/a.png) and [a link](b.png)";
+        let rewritten = rewrite_image_sources(markdown, |src| (!src.starts_with("http")).then(|| "x".to_string()));
+        assert_eq!(rewritten, markdown);
+    }
+}
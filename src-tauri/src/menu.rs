@@ -1,91 +1,296 @@
-use tauri::menu::{Menu, MenuBuilder, SubmenuBuilder, MenuItemBuilder, CheckMenuItemBuilder, CheckMenuItem};
+use tauri::menu::{Menu, MenuBuilder, SubmenuBuilder, MenuItem, MenuItemBuilder, CheckMenuItemBuilder, CheckMenuItem};
 use tauri::{Manager, Emitter};
+use crate::fonts::{
+    font_family_label, same_line_height, DEFAULT_FONT_FAMILY, DEFAULT_FONT_SIZE, DEFAULT_LINE_HEIGHT,
+    GENERIC_FONT_FAMILIES, LINE_HEIGHTS,
+};
+use crate::keybindings::{accelerator_for, menu_action};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-// Store for font menu items
-pub static FONT_MENU_ITEMS: once_cell::sync::Lazy<Arc<Mutex<Option<FontMenuItems>>>> =
+/// Prefix of the Style menu ids that set the font family, followed by "system", "serif" or "monospace"
+pub const FONT_FAMILY_MENU_PREFIX: &str = "font_family_";
+/// Style menu id of the focused note's installed font, listed only while it uses one
+const CUSTOM_FONT_FAMILY_MENU_ID: &str = "font_family_custom";
+/// Prefix of the Style menu ids that set the line height, followed by the preset's lowercase label
+pub const LINE_HEIGHT_MENU_PREFIX: &str = "line_height_";
+
+// Store for style menu items
+pub static STYLE_MENU_ITEMS: once_cell::sync::Lazy<Arc<Mutex<Option<StyleMenuItems>>>> =
     once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
 
-pub struct FontMenuItems {
-    pub small: CheckMenuItem<tauri::Wry>,
-    pub medium: CheckMenuItem<tauri::Wry>,
-    pub large: CheckMenuItem<tauri::Wry>,
-    pub xlarge: CheckMenuItem<tauri::Wry>,
+// Check item reflecting whether the local API is enabled
+pub static API_MENU_ITEM: once_cell::sync::Lazy<Mutex<Option<CheckMenuItem<tauri::Wry>>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+pub struct StyleMenuItems {
+    /// Disabled item showing the focused note's font size
+    pub font_size: MenuItem<tauri::Wry>,
+    pub font_families: Vec<(String, CheckMenuItem<tauri::Wry>)>,
+    pub line_heights: Vec<(f64, CheckMenuItem<tauri::Wry>)>,
+}
+
+/// The focused note's font, as shown in the Style menu
+#[derive(Debug, Clone)]
+pub struct CheckedStyle {
+    pub font_size: u32,
+    pub font_family: String,
+    pub line_height: f64,
+}
+
+// Font of the focused note, so a rebuilt menu keeps its checks
+pub static CHECKED_STYLE: once_cell::sync::Lazy<Mutex<CheckedStyle>> = once_cell::sync::Lazy::new(|| {
+    Mutex::new(CheckedStyle {
+        font_size: DEFAULT_FONT_SIZE,
+        font_family: DEFAULT_FONT_FAMILY.to_string(),
+        line_height: DEFAULT_LINE_HEIGHT,
+    })
+});
+
+// Notes listed in the Window menu (id and label), to rebuild it only when the list changes
+static WINDOW_MENU_NOTES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+// Window menu check item of each listed note
+static WINDOW_MENU_ITEMS: Mutex<Vec<(String, CheckMenuItem<tauri::Wry>)>> = Mutex::new(Vec::new());
+
+// The last focused note, checked in the Window menu
+static FOCUSED_NOTE: Mutex<Option<String>> = Mutex::new(None);
+
+// Group menu check item of each group, checked for the focused note's group
+static GROUP_MENU_ITEMS: Mutex<Vec<(String, CheckMenuItem<tauri::Wry>)>> = Mutex::new(Vec::new());
+
+fn font_size_label(font_size: u32) -> String {
+    format!("Font Size: {}px", font_size)
+}
+
+/// A menu item with its label and the shortcut from keybindings.json
+fn action_item<M: Manager<tauri::Wry>>(app: &M, id: &str) -> tauri::Result<MenuItem<tauri::Wry>> {
+    let label = menu_action(id).map(|action| action.label).unwrap_or(id);
+    let mut builder = MenuItemBuilder::with_id(id, label);
+    if let Some(accelerator) = accelerator_for(id) {
+        builder = builder.accelerator(accelerator);
+    }
+    builder.build(app)
+}
+
+fn check_item<M: Manager<tauri::Wry>>(app: &M, id: &str, checked: bool) -> tauri::Result<CheckMenuItem<tauri::Wry>> {
+    let label = menu_action(id).map(|action| action.label).unwrap_or(id);
+    let mut builder = CheckMenuItemBuilder::with_id(id, label).checked(checked);
+    if let Some(accelerator) = accelerator_for(id) {
+        builder = builder.accelerator(accelerator);
+    }
+    builder.build(app)
 }
 
-pub fn create_menu(app: &tauri::App) -> Result<Menu<tauri::Wry>, tauri::Error> {
+pub fn create_menu<M: Manager<tauri::Wry>>(app: &M) -> Result<Menu<tauri::Wry>, tauri::Error> {
+    #[cfg(target_os = "macos")]
     use tauri::menu::PredefinedMenuItem;
 
     // App Menu (PeachLeaf) - macOS only
     #[cfg(target_os = "macos")]
     let app_menu = SubmenuBuilder::new(app, "PeachLeaf")
         .item(&PredefinedMenuItem::hide(app, None)?)
-        .item(&MenuItemBuilder::new("Quit PeachLeaf").id("quit_app").accelerator("CmdOrCtrl+Q").build(app)?)
+        .item(&action_item(app, "quit_app")?)
         .build()?;
 
+    let api_item = check_item(app, "toggle_api", crate::state::load_config().api.enabled)?;
+    *API_MENU_ITEM.lock().unwrap() = Some(api_item.clone());
+
     // File Menu
     let file_menu = SubmenuBuilder::new(app, "File")
-        .item(&MenuItemBuilder::new("New Note").id("new_note").accelerator("CmdOrCtrl+N").build(app)?)
-        .item(&MenuItemBuilder::new("Close Note").id("close_note").accelerator("CmdOrCtrl+W").build(app)?)
+        .item(&action_item(app, "new_note")?)
+        .item(&action_item(app, "open_file")?)
+        .item(&action_item(app, "close_note")?)
+        .item(&action_item(app, "set_reminder")?)
         .separator()
-        .item(&MenuItemBuilder::new("Print...").id("print").accelerator("CmdOrCtrl+P").build(app)?)
+        .item(&action_item(app, "import_markdown_folder")?)
+        .item(&action_item(app, "import_notes")?)
+        .item(&action_item(app, "import_note_bundle")?)
+        .item(&action_item(app, "export_note_bundle")?)
+        .item(&action_item(app, "export_note")?)
+        .item(&action_item(app, "print")?)
+        .separator()
+        .item(&action_item(app, "use_vault")?)
+        .item(&action_item(app, "use_default_notes")?)
+        .separator()
+        .item(&api_item)
         .build()?;
 
     // Edit Menu
     let edit_menu = SubmenuBuilder::new(app, "Edit")
-        .item(&MenuItemBuilder::new("Undo").id("undo").accelerator("CmdOrCtrl+Z").build(app)?)
-        .item(&MenuItemBuilder::new("Redo").id("redo").accelerator("CmdOrCtrl+Shift+Z").build(app)?)
+        .item(&action_item(app, "undo")?)
+        .item(&action_item(app, "redo")?)
         .separator()
-        .item(&MenuItemBuilder::new("Cut").id("cut").accelerator("CmdOrCtrl+X").build(app)?)
-        .item(&MenuItemBuilder::new("Copy").id("copy").accelerator("CmdOrCtrl+C").build(app)?)
-        .item(&MenuItemBuilder::new("Paste").id("paste").accelerator("CmdOrCtrl+V").build(app)?)
+        .item(&action_item(app, "cut")?)
+        .item(&action_item(app, "copy")?)
+        .item(&action_item(app, "paste")?)
+        .separator()
+        .item(&action_item(app, "copy_note_as")?)
         .build()?;
 
-    // Font Menu with check items (default is Medium - 14px)
-    let font_small = CheckMenuItemBuilder::with_id("font_small", "Small (12px)")
-        .checked(false)
-        .build(app)?;
-    let font_medium = CheckMenuItemBuilder::with_id("font_medium", "Medium (14px)")
-        .checked(true)
-        .build(app)?;
-    let font_large = CheckMenuItemBuilder::with_id("font_large", "Large (16px)")
-        .checked(false)
-        .build(app)?;
-    let font_xlarge = CheckMenuItemBuilder::with_id("font_xlarge", "Extra Large (18px)")
-        .checked(false)
+    // Font items reflecting the focused note's font
+    let style = CHECKED_STYLE.lock().unwrap().clone();
+    let font_size_item = MenuItemBuilder::with_id("font_size_current", font_size_label(style.font_size))
+        .enabled(false)
         .build(app)?;
 
-    // Store font menu items for later access
-    {
-        let mut items = FONT_MENU_ITEMS.lock().unwrap();
-        *items = Some(FontMenuItems {
-            small: font_small.clone(),
-            medium: font_medium.clone(),
-            large: font_large.clone(),
-            xlarge: font_xlarge.clone(),
-        });
+    let mut font_families = Vec::new();
+    for (family, _) in GENERIC_FONT_FAMILIES {
+        let id = format!("{}{}", FONT_FAMILY_MENU_PREFIX, family);
+        font_families.push((family.to_string(), check_item(app, &id, style.font_family == family)?));
+    }
+    // An installed font is listed by name while the focused note uses it
+    if !font_families.iter().any(|(family, _)| *family == style.font_family) {
+        let item = CheckMenuItemBuilder::with_id(CUSTOM_FONT_FAMILY_MENU_ID, font_family_label(&style.font_family))
+            .checked(true)
+            .build(app)?;
+        font_families.push((style.font_family.clone(), item));
+    }
+
+    let mut line_heights = Vec::new();
+    for (line_height, label) in LINE_HEIGHTS {
+        let id = format!("{}{}", LINE_HEIGHT_MENU_PREFIX, label.to_lowercase());
+        line_heights.push((line_height, check_item(app, &id, same_line_height(style.line_height, line_height))?));
+    }
+
+    let mut font_family_menu = SubmenuBuilder::new(app, "Font");
+    for (_, item) in &font_families {
+        font_family_menu = font_family_menu.item(item);
+    }
+    let font_family_menu = font_family_menu
+        .separator()
+        .item(&action_item(app, "custom_font")?);
+    let mut line_height_menu = SubmenuBuilder::new(app, "Line Height");
+    for (_, item) in &line_heights {
+        line_height_menu = line_height_menu.item(item);
     }
 
     // Style Menu - combines color and font options (color first)
     let style_menu = SubmenuBuilder::new(app, "Style")
-        .item(&MenuItemBuilder::new("Choose Color...").id("open_color_picker").build(app)?)
+        .item(&action_item(app, "open_color_picker")?)
+        .separator()
+        .item(&font_size_item)
+        .item(&action_item(app, "font_increase")?)
+        .item(&action_item(app, "font_decrease")?)
+        .item(&action_item(app, "font_reset")?)
         .separator()
-        .item(&font_small)
-        .item(&font_medium)
-        .item(&font_large)
-        .item(&font_xlarge)
+        .item(&font_family_menu.build()?)
+        .item(&line_height_menu.build()?)
         .build()?;
 
-    // Window Menu
-    let window_menu = SubmenuBuilder::new(app, "Window")
-        .item(&MenuItemBuilder::new("Minimize").id("minimize").build(app)?)
-        .item(&MenuItemBuilder::new("Zoom").id("zoom").build(app)?)
+    // Store style menu items for later access
+    *STYLE_MENU_ITEMS.lock().unwrap() = Some(StyleMenuItems {
+        font_size: font_size_item,
+        font_families,
+        line_heights,
+    });
+
+    // Window Menu, listing every open note with a check on the focused one
+    let focused_note = FOCUSED_NOTE.lock().unwrap().clone();
+    let mut note_items = Vec::new();
+    for (id, label) in WINDOW_MENU_NOTES.lock().unwrap().iter() {
+        let item = CheckMenuItemBuilder::with_id(format!("{}{}", crate::tray::FOCUS_NOTE_MENU_PREFIX, id), label)
+            .checked(focused_note.as_deref() == Some(id.as_str()))
+            .build(app)?;
+        note_items.push((id.clone(), item));
+    }
+
+    // Arrange submenu: grid, cascade and alignment of the notes on the focused note's monitor
+    let mut arrange_menu = SubmenuBuilder::new(app, "Arrange")
+        .item(&action_item(app, "tile_notes")?)
+        .item(&action_item(app, "cascade_notes")?)
+        .separator();
+    for alignment in crate::arrange::Alignment::ALL {
+        arrange_menu = arrange_menu.item(&action_item(app, alignment.menu_id())?);
+    }
+    // Not offered where a drag cannot be told apart from a pause (see CAN_SNAP_WHILE_DRAGGING)
+    let snap_item = check_item(
+        app,
+        "snap_notes",
+        crate::window_manager::CAN_SNAP_WHILE_DRAGGING && crate::state::load_config().arrange.snap_while_dragging,
+    )?;
+    snap_item.set_enabled(crate::window_manager::CAN_SNAP_WHILE_DRAGGING)?;
+    let arrange_menu = arrange_menu.separator().item(&snap_item).build()?;
+
+    // Group submenu: the focused note's group is checked, and each group can be shown, hidden,
+    // tiled or deleted as a whole
+    let groups = crate::groups::groups();
+    let focused_group = focused_note.as_deref().and_then(crate::groups::note_group);
+    let mut group_items = Vec::new();
+    let mut group_menu = SubmenuBuilder::new(app, "Group")
+        .item(&action_item(app, "new_group")?)
+        .item(&action_item(app, "leave_group")?)
+        .item(&action_item(app, "color_group")?);
+    if !groups.is_empty() {
+        group_menu = group_menu.separator();
+        for group in &groups {
+            let item = CheckMenuItemBuilder::with_id(format!("{}{}", crate::groups::JOIN_GROUP_MENU_PREFIX, group.id), &group.name)
+                .checked(focused_group.as_deref() == Some(group.id.as_str()))
+                .build(app)?;
+            group_menu = group_menu.item(&item);
+            group_items.push((group.id.clone(), item));
+        }
+        group_menu = group_menu.separator();
+        for (title, prefix) in [
+            ("Show Group", crate::groups::SHOW_GROUP_MENU_PREFIX),
+            ("Hide Group", crate::groups::HIDE_GROUP_MENU_PREFIX),
+            ("Tile Group", crate::groups::TILE_GROUP_MENU_PREFIX),
+            ("Delete Group", crate::groups::DELETE_GROUP_MENU_PREFIX),
+        ] {
+            let mut submenu = SubmenuBuilder::new(app, title);
+            for group in &groups {
+                submenu = submenu.item(&MenuItemBuilder::with_id(format!("{}{}", prefix, group.id), &group.name).build(app)?);
+            }
+            group_menu = group_menu.item(&submenu.build()?);
+        }
+    }
+    let group_menu = group_menu.build()?;
+    *GROUP_MENU_ITEMS.lock().unwrap() = group_items;
+
+    // Layouts submenu: saved arrangements with a check on the active one, and deleting them
+    let layouts = crate::layouts::load_layouts();
+    let mut layouts_menu = SubmenuBuilder::new(app, "Layouts").item(&action_item(app, "save_layout")?);
+    if !layouts.layouts.is_empty() {
+        let mut delete_menu = SubmenuBuilder::new(app, "Delete Layout");
+        layouts_menu = layouts_menu.separator();
+        for layout in &layouts.layouts {
+            let item = CheckMenuItemBuilder::with_id(format!("{}{}", crate::layouts::APPLY_LAYOUT_MENU_PREFIX, layout.name), &layout.name)
+                .checked(layouts.active.as_deref() == Some(layout.name.as_str()))
+                .build(app)?;
+            layouts_menu = layouts_menu.item(&item);
+            delete_menu = delete_menu.item(
+                &MenuItemBuilder::with_id(format!("{}{}", crate::layouts::DELETE_LAYOUT_MENU_PREFIX, layout.name), &layout.name).build(app)?,
+            );
+        }
+        layouts_menu = layouts_menu.separator().item(&delete_menu.build()?);
+    }
+    let layouts_menu = layouts_menu.build()?;
+
+    let mut window_menu = SubmenuBuilder::new(app, "Window")
+        .item(&action_item(app, "minimize")?)
+        .item(&action_item(app, "zoom")?)
+        .item(&arrange_menu)
+        .item(&layouts_menu)
+        .item(&group_menu)
+        .separator();
+    for (_, item) in &note_items {
+        window_menu = window_menu.item(item);
+    }
+    if !note_items.is_empty() {
+        window_menu = window_menu.separator();
+    }
+    let window_menu = window_menu
+        .item(&action_item(app, "show_all")?)
+        .item(&action_item(app, "hide_all")?)
+        .separator()
+        .item(&action_item(app, "all_tasks")?)
         .build()?;
 
+    *WINDOW_MENU_ITEMS.lock().unwrap() = note_items;
+
     // Help Menu
     let help_menu = SubmenuBuilder::new(app, "Help")
-        .item(&MenuItemBuilder::new("About PeachLeaf").id("about").build(app)?)
+        .item(&action_item(app, "about")?)
         .build()?;
 
     // Build the menu bar
@@ -111,16 +316,124 @@ pub fn create_menu(app: &tauri::App) -> Result<Menu<tauri::Wry>, tauri::Error> {
     Ok(menu)
 }
 
-/// Update font menu checks based on font size
-pub fn update_font_menu_checks(font_size: u32) {
-    if let Some(ref items) = *FONT_MENU_ITEMS.lock().unwrap() {
-        let _ = items.small.set_checked(font_size == 12);
-        let _ = items.medium.set_checked(font_size == 14);
-        let _ = items.large.set_checked(font_size == 16);
-        let _ = items.xlarge.set_checked(font_size == 18);
+/// Rebuilds the menu bar, e.g. after keyboard shortcuts changed
+pub fn rebuild_menu(app: &tauri::AppHandle) {
+    match create_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = app.set_menu(menu) {
+                eprintln!("Failed to set rebuilt menu: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Failed to rebuild menu: {}", e),
+    }
+}
+
+/// Shows a note's font size, font family and line height in the Style menu.
+/// Rebuilds the menu only when an installed font has to be listed or removed.
+pub fn update_style_menu(app: &tauri::AppHandle, font_size: u32, font_family: &str, line_height: f64) {
+    *CHECKED_STYLE.lock().unwrap() = CheckedStyle {
+        font_size,
+        font_family: font_family.to_string(),
+        line_height,
+    };
+
+    let needs_rebuild = {
+        let items = STYLE_MENU_ITEMS.lock().unwrap();
+        let Some(items) = items.as_ref() else {
+            return;
+        };
+        let _ = items.font_size.set_text(font_size_label(font_size));
+        for (family, item) in &items.font_families {
+            let _ = item.set_checked(family == font_family);
+        }
+        for (value, item) in &items.line_heights {
+            let _ = item.set_checked(same_line_height(*value, line_height));
+        }
+
+        let listed_custom = items.font_families.len() > GENERIC_FONT_FAMILIES.len();
+        let is_custom = !GENERIC_FONT_FAMILIES.iter().any(|(family, _)| *family == font_family);
+        listed_custom != is_custom || (is_custom && !items.font_families.iter().any(|(family, _)| family == font_family))
+    };
+    if needs_rebuild {
+        rebuild_menu(app);
     }
 }
 
+/// Puts the Style menu checks back on the focused note's font, e.g. after a click toggled one
+fn refresh_style_menu(app: &tauri::AppHandle) {
+    let style = CHECKED_STYLE.lock().unwrap().clone();
+    update_style_menu(app, style.font_size, &style.font_family, style.line_height);
+}
+
+/// Changes the focused note's font from a Style menu item
+fn set_focused_note_font(
+    app: &tauri::AppHandle,
+    font_size: impl FnOnce(u32) -> Option<u32>,
+    font_family: Option<String>,
+    line_height: Option<f64>,
+) {
+    let focused_note = app.webview_windows().into_values().find(|w| {
+        w.is_focused().unwrap_or(false) && crate::window_manager::is_note_window(w.label())
+    });
+    let Some(focused_note) = focused_note else {
+        refresh_style_menu(app);
+        return;
+    };
+
+    let note_id = focused_note.label().to_string();
+    let current_size = crate::commands::window::WINDOW_METADATA.lock().unwrap()
+        .get(&note_id)
+        .map_or(DEFAULT_FONT_SIZE, |data| data.font_size);
+    let font_size = font_size(current_size);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::commands::window::set_note_font(app.clone(), note_id, font_size, font_family, line_height).await {
+            eprintln!("Failed to change font: {}", e);
+            refresh_style_menu(&app);
+        }
+    });
+}
+
+/// Lists the given notes in the Window menu, rebuilding the menu only if titles or visibility changed
+pub fn update_window_menu_notes(app: &tauri::AppHandle, notes: &[crate::models::NoteSummary]) {
+    let entries = crate::tray::note_menu_entries(notes);
+    {
+        let mut listed = WINDOW_MENU_NOTES.lock().unwrap();
+        if *listed == entries {
+            return;
+        }
+        *listed = entries;
+    }
+    rebuild_menu(app);
+}
+
+/// Moves the Window menu check to the focused note
+pub fn update_window_menu_checks(focused_note: &str) {
+    *FOCUSED_NOTE.lock().unwrap() = Some(focused_note.to_string());
+    for (id, item) in WINDOW_MENU_ITEMS.lock().unwrap().iter() {
+        let _ = item.set_checked(id == focused_note);
+    }
+    update_group_menu_checks();
+}
+
+/// Checks the focused note's group in the Group menu
+pub fn update_group_menu_checks() {
+    let focused_note = FOCUSED_NOTE.lock().unwrap().clone();
+    let group = focused_note.as_deref().and_then(crate::groups::note_group);
+    for (id, item) in GROUP_MENU_ITEMS.lock().unwrap().iter() {
+        let _ = item.set_checked(group.as_deref() == Some(id.as_str()));
+    }
+}
+
+/// The focused note window's id
+fn focused_note_id(app: &tauri::AppHandle) -> Option<String> {
+    app.webview_windows()
+        .into_values()
+        .find(|w| w.is_focused().unwrap_or(false) && crate::window_manager::is_note_window(w.label()))
+        .map(|w| w.label().to_string())
+}
+
 pub fn setup_menu_handler(app: &tauri::AppHandle) {
     use crate::window_manager::create_new_note_backend;
 
@@ -170,6 +483,18 @@ pub fn setup_menu_handler(app: &tauri::AppHandle) {
             return;
         }
 
+        // Handle open_file: open an existing markdown file as a note
+        if menu_id == "open_file" {
+            println!("Handling open_file in backend");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::file::select_file(app, None).await {
+                    eprintln!("Failed to open file: {}", e);
+                }
+            });
+            return;
+        }
+
         // Handle close_note: emit ONLY to focused window
         if menu_id == "close_note" {
             println!("Handling close_note in backend");
@@ -197,6 +522,40 @@ pub fn setup_menu_handler(app: &tauri::AppHandle) {
             return;
         }
 
+        // Handle set_reminder: the focused note shows its reminder for editing
+        if menu_id == "set_reminder" {
+            println!("Handling set_reminder in backend");
+            if let Some(note_id) = focused_note_id(app) {
+                let reminder = crate::commands::window::find_note_data(&note_id).and_then(|note| note.reminder);
+                if let Some(window) = app.get_webview_window(&note_id) {
+                    let _ = window.emit(&format!("set_reminder_{}", note_id), reminder);
+                }
+            }
+            return;
+        }
+
+        // Handle custom_font: the focused note asks for a font name
+        if menu_id == "custom_font" {
+            println!("Handling custom_font in backend");
+            if let Some(note_id) = focused_note_id(app) {
+                if let Some(window) = app.get_webview_window(&note_id) {
+                    let _ = window.emit(&format!("custom_font_{}", note_id), ());
+                }
+            }
+            return;
+        }
+
+        // Handle copy_note_as: the focused note asks which formats to copy
+        if menu_id == "copy_note_as" {
+            println!("Handling copy_note_as in backend");
+            if let Some(note_id) = focused_note_id(app) {
+                if let Some(window) = app.get_webview_window(&note_id) {
+                    let _ = window.emit(&format!("copy_note_as_{}", note_id), ());
+                }
+            }
+            return;
+        }
+
         // Handle print: emit to focused window only
         if menu_id == "print" {
             println!("Handling print in backend");
@@ -210,18 +569,371 @@ pub fn setup_menu_handler(app: &tauri::AppHandle) {
             return;
         }
 
-        // Handle font menu items: update check state
-        if menu_id.starts_with("font_") {
-            let font_size = match menu_id {
-                "font_small" => 12,
-                "font_medium" => 14,
-                "font_large" => 16,
-                "font_xlarge" => 18,
-                _ => 14,
+        // Handle export_note: export the focused note
+        if menu_id == "export_note" {
+            println!("Handling export_note in backend");
+            if let Some(focused_window) = app.webview_windows().values().find(|w| {
+                w.is_focused().unwrap_or(false)
+            }) {
+                let window_label = focused_window.label().to_string();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::commands::export::export_note(app, window_label).await {
+                        eprintln!("Failed to export note: {}", e);
+                    }
+                });
+            }
+            return;
+        }
+
+        // Handle export_note_bundle: export the focused note as a bundle
+        if menu_id == "export_note_bundle" {
+            println!("Handling export_note_bundle in backend");
+            if let Some(focused_window) = app.webview_windows().values().find(|w| {
+                w.is_focused().unwrap_or(false)
+            }) {
+                let window_label = focused_window.label().to_string();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::commands::bundle::export_note_bundle(app, window_label).await {
+                        eprintln!("Failed to export note bundle: {}", e);
+                    }
+                });
+            }
+            return;
+        }
+
+        // Handle import_markdown_folder: import every markdown file in a folder
+        if menu_id == "import_markdown_folder" {
+            println!("Handling import_markdown_folder in backend");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::import::import_markdown_folder(app, None, None).await {
+                    eprintln!("Failed to import folder: {}", e);
+                }
+            });
+            return;
+        }
+
+        // Handle import_notes: import notes exported from other sticky-note tools
+        if menu_id == "import_notes" {
+            println!("Handling import_notes in backend");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::import::import_notes(app, None, None).await {
+                    eprintln!("Failed to import notes: {}", e);
+                }
+            });
+            return;
+        }
+
+        // Handle minimize: minimize the focused window
+        if menu_id == "minimize" {
+            println!("Handling minimize in backend");
+            if let Some(focused_window) = app.webview_windows().values().find(|w| {
+                w.is_focused().unwrap_or(false)
+            }) {
+                if let Err(e) = focused_window.minimize() {
+                    eprintln!("Failed to minimize window: {}", e);
+                }
+            }
+            return;
+        }
+
+        // Handle zoom: maximize the focused window, or restore it if already maximized
+        if menu_id == "zoom" {
+            println!("Handling zoom in backend");
+            if let Some(focused_window) = app.webview_windows().values().find(|w| {
+                w.is_focused().unwrap_or(false)
+            }) {
+                let result = if focused_window.is_maximized().unwrap_or(false) {
+                    focused_window.unmaximize()
+                } else {
+                    focused_window.maximize()
+                };
+                if let Err(e) = result {
+                    eprintln!("Failed to zoom window: {}", e);
+                }
+            }
+            return;
+        }
+
+        // Handle tile_notes and cascade_notes: arrange the notes on the focused note's monitor
+        if menu_id == "tile_notes" || menu_id == "cascade_notes" {
+            println!("Handling {} in backend", menu_id);
+            let result = if menu_id == "tile_notes" {
+                crate::window_manager::tile_notes(app, None)
+            } else {
+                crate::window_manager::cascade_notes(app, None)
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to arrange notes: {}", e);
+            }
+            return;
+        }
+
+        // Handle alignment items: line the notes on the focused note's monitor up with it
+        if let Some(alignment) = crate::arrange::Alignment::ALL.into_iter().find(|alignment| alignment.menu_id() == menu_id) {
+            println!("Handling {} in backend", menu_id);
+            if let Err(e) = crate::window_manager::align_notes(app, &[], alignment) {
+                eprintln!("Failed to align notes: {}", e);
+            }
+            return;
+        }
+
+        // Handle snap_notes: turn snapping of dragged notes on or off (the check item toggles itself)
+        if menu_id == "snap_notes" {
+            println!("Handling snap_notes in backend");
+            let mut config = crate::state::load_config();
+            config.arrange.snap_while_dragging = !config.arrange.snap_while_dragging;
+            if let Err(e) = crate::state::save_config(&config) {
+                eprintln!("Failed to save snapping setting: {}", e);
+            }
+            return;
+        }
+
+        // Handle new_group: put the focused note in a new group
+        if menu_id == "new_group" {
+            println!("Handling new_group in backend");
+            let note_ids: Vec<String> = focused_note_id(app).into_iter().collect();
+            if let Err(e) = crate::groups::create_group(app, None, &note_ids) {
+                eprintln!("Failed to create group: {}", e);
+            }
+            return;
+        }
+
+        // Handle leave_group and group items: move the focused note out of its group or into another
+        let join_group = menu_id.strip_prefix(crate::groups::JOIN_GROUP_MENU_PREFIX);
+        if menu_id == "leave_group" || join_group.is_some() {
+            println!("Handling {} in backend", menu_id);
+            match focused_note_id(app) {
+                Some(note_id) => {
+                    if let Err(e) = crate::groups::set_note_group(app, &note_id, join_group) {
+                        eprintln!("Failed to change group: {}", e);
+                    }
+                }
+                // Clicking a check item toggles it, so put the checks back
+                None => update_group_menu_checks(),
+            }
+            return;
+        }
+
+        // Handle color_group: give the focused note's group its color
+        if menu_id == "color_group" {
+            println!("Handling color_group in backend");
+            let Some(note_id) = focused_note_id(app) else {
+                return;
+            };
+            let data = crate::commands::window::WINDOW_METADATA.lock().unwrap().get(&note_id).cloned();
+            if let Some((group, color)) = data.and_then(|data| data.group.map(|group| (group, data.background_color))) {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::groups::set_group_color(&app, &group, &color).await {
+                        eprintln!("Failed to color group: {}", e);
+                    }
+                });
+            }
+            return;
+        }
+
+        if let Some(group_id) = menu_id.strip_prefix(crate::groups::SHOW_GROUP_MENU_PREFIX) {
+            println!("Handling show of group {} in backend", group_id);
+            if let Err(e) = crate::groups::set_group_visible(app, group_id, true) {
+                eprintln!("Failed to show group: {}", e);
+            }
+            return;
+        }
+
+        if let Some(group_id) = menu_id.strip_prefix(crate::groups::HIDE_GROUP_MENU_PREFIX) {
+            println!("Handling hide of group {} in backend", group_id);
+            if let Err(e) = crate::groups::set_group_visible(app, group_id, false) {
+                eprintln!("Failed to hide group: {}", e);
+            }
+            return;
+        }
+
+        if let Some(group_id) = menu_id.strip_prefix(crate::groups::TILE_GROUP_MENU_PREFIX) {
+            println!("Handling tiling of group {} in backend", group_id);
+            if let Err(e) = crate::window_manager::tile_notes(app, Some(group_id)) {
+                eprintln!("Failed to tile group: {}", e);
+            }
+            return;
+        }
+
+        if let Some(group_id) = menu_id.strip_prefix(crate::groups::DELETE_GROUP_MENU_PREFIX) {
+            println!("Handling deletion of group {} in backend", group_id);
+            if let Err(e) = crate::groups::delete_group(app, group_id) {
+                eprintln!("Failed to delete group: {}", e);
+            }
+            return;
+        }
+
+        // Handle save_layout: save the notes' arrangement for the connected monitors
+        if menu_id == "save_layout" {
+            println!("Handling save_layout in backend");
+            if let Err(e) = crate::layouts::save_layout(app, None) {
+                eprintln!("Failed to save layout: {}", e);
+            }
+            return;
+        }
+
+        // Handle layout items: switch to the layout (the menu is rebuilt with the new check)
+        if let Some(name) = menu_id.strip_prefix(crate::layouts::APPLY_LAYOUT_MENU_PREFIX) {
+            println!("Handling layout {} in backend", name);
+            if let Err(e) = crate::layouts::apply_layout(app, name) {
+                eprintln!("Failed to apply layout: {}", e);
+                rebuild_menu(app);
+            }
+            return;
+        }
+
+        if let Some(name) = menu_id.strip_prefix(crate::layouts::DELETE_LAYOUT_MENU_PREFIX) {
+            println!("Handling deletion of layout {} in backend", name);
+            if let Err(e) = crate::layouts::delete_layout(app, name) {
+                eprintln!("Failed to delete layout: {}", e);
+            }
+            return;
+        }
+
+        // Handle show_all: reveal hidden notes
+        if menu_id == "show_all" {
+            println!("Handling show_all in backend");
+            crate::window_manager::show_all_notes(app);
+            return;
+        }
+
+        // Handle all_tasks: open the window listing every note's tasks
+        if menu_id == "all_tasks" {
+            println!("Handling all_tasks in backend");
+            if let Err(e) = crate::tasks::open_tasks_window(app) {
+                eprintln!("Failed to open All Tasks window: {}", e);
+            }
+            return;
+        }
+
+        // Handle hide_all: hide every note, e.g. from the tray
+        if menu_id == "hide_all" {
+            println!("Handling hide_all in backend");
+            crate::window_manager::hide_all_notes(app);
+            return;
+        }
+
+        // Handle note entries of the tray and Window menus: bring that note to the front
+        if let Some(note_id) = menu_id.strip_prefix(crate::tray::FOCUS_NOTE_MENU_PREFIX) {
+            println!("Handling focus of note {} in backend", note_id);
+            // Clicking a check item toggles it, so set the checks even if the note already had focus
+            update_window_menu_checks(note_id);
+            let app = app.clone();
+            let note_id = note_id.to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::window::focus_note(app, note_id).await {
+                    eprintln!("Failed to focus note: {}", e);
+                }
+            });
+            return;
+        }
+
+        // Handle import_note_bundle: pick a bundle and open it as a new note
+        if menu_id == "import_note_bundle" {
+            println!("Handling import_note_bundle in backend");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::bundle::import_note_bundle(app, None).await {
+                    eprintln!("Failed to import note bundle: {}", e);
+                }
+            });
+            return;
+        }
+
+        // Handle use_vault: pick a markdown folder to keep notes in
+        if menu_id == "use_vault" {
+            println!("Handling use_vault in backend");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::vault::set_vault_folder(app, None, None).await {
+                    eprintln!("Failed to switch to vault: {}", e);
+                }
+            });
+            return;
+        }
+
+        // Handle use_default_notes: leave vault mode
+        if menu_id == "use_default_notes" {
+            println!("Handling use_default_notes in backend");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::vault::use_default_notes_folder(app).await {
+                    eprintln!("Failed to switch to default notes folder: {}", e);
+                }
+            });
+            return;
+        }
+
+        // Handle toggle_api: turn the local API on or off and show how to connect
+        if menu_id == "toggle_api" {
+            println!("Handling toggle_api in backend");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let enabled = !crate::state::load_config().api.enabled;
+                let result = crate::commands::api::set_api_enabled(app.clone(), enabled).await;
+
+                let now_enabled = result.as_ref().map(|config| config.enabled).unwrap_or(!enabled);
+                if let Some(item) = API_MENU_ITEM.lock().unwrap().as_ref() {
+                    let _ = item.set_checked(now_enabled);
+                }
+
+                use tauri_plugin_dialog::DialogExt;
+                match result {
+                    Ok(config) if config.enabled => {
+                        app.dialog()
+                            .message(format!(
+                                "The local API is listening on http://127.0.0.1:{}\n\nSend this token as \"Authorization: Bearer <token>\":\n{}",
+                                config.port, config.token
+                            ))
+                            .title("Local API")
+                            .show(|_| {});
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("Failed to toggle local API: {}", e);
+                        app.dialog().message(e).title("Local API").show(|_| {});
+                    }
+                }
+            });
+            return;
+        }
+
+        // Handle font size items: step or reset the focused note's size
+        if matches!(menu_id, "font_increase" | "font_decrease" | "font_reset") {
+            println!("Handling {} in backend", menu_id);
+            let font_size: fn(u32) -> Option<u32> = match menu_id {
+                "font_increase" => |size| Some(size + 1),
+                "font_decrease" => |size| Some(size.saturating_sub(1)),
+                _ => |_| Some(DEFAULT_FONT_SIZE),
             };
+            set_focused_note_font(app, font_size, None, None);
+            return;
+        }
 
-            // Update menu check state
-            update_font_menu_checks(font_size);
+        // Handle font family items; the installed font entry is already the note's font
+        if let Some(family) = menu_id.strip_prefix(FONT_FAMILY_MENU_PREFIX) {
+            println!("Handling font family {} in backend", family);
+            if menu_id == CUSTOM_FONT_FAMILY_MENU_ID {
+                // Clicking toggled its check, so put the checks back
+                refresh_style_menu(app);
+            } else {
+                set_focused_note_font(app, |_| None, Some(family.to_string()), None);
+            }
+            return;
+        }
+
+        // Handle line height items
+        if let Some(preset) = menu_id.strip_prefix(LINE_HEIGHT_MENU_PREFIX) {
+            println!("Handling line height {} in backend", preset);
+            if let Some((line_height, _)) = LINE_HEIGHTS.iter().find(|(_, label)| label.eq_ignore_ascii_case(preset)) {
+                set_focused_note_font(app, |_| None, None, Some(*line_height));
+            }
+            return;
         }
 
         // Emit event to the focused window (for other menu items) - use window-specific event
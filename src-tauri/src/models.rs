@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize};
 
 fn default_font_size() -> u32 {
-    14
+    crate::fonts::DEFAULT_FONT_SIZE
+}
+
+fn default_font_family() -> String {
+    crate::fonts::DEFAULT_FONT_FAMILY.to_string()
+}
+
+fn default_line_height() -> f64 {
+    crate::fonts::DEFAULT_LINE_HEIGHT
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,15 +26,233 @@ pub struct StickerData {
     pub mode: String,
     #[serde(default = "default_font_size")]
     pub font_size: u32,
+    /// "system", "serif", "monospace" or the name of an installed font
+    #[serde(default = "default_font_family")]
+    pub font_family: String,
+    #[serde(default = "default_line_height")]
+    pub line_height: f64,
     #[serde(default)]
     pub monitor_name: Option<String>,
     #[serde(default)]
     pub monitor_position: Option<(i32, i32)>,
     #[serde(default)]
     pub monitor_size: Option<(u32, u32)>,
+    /// The monitor's scale factor when saved. When set, `x` and `y` are relative to the monitor's
+    /// top-left corner in its logical pixels; otherwise they are absolute.
+    #[serde(default)]
+    pub monitor_scale_factor: Option<f64>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub hidden: bool,
+    /// Id of the group the note belongs to
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub reminder: Option<Reminder>,
+    /// Title from the note's markdown, cached while its window is open so listing notes reads no files
+    #[serde(skip)]
+    pub content_title: Option<String>,
+}
+
+impl StickerData {
+    /// Data for a new note window with the default size, colors and a cascaded position
+    pub fn new_note(id: String, file_path: String) -> Self {
+        // Same offset scheme as create_new_note_backend, derived from the id's timestamp
+        let offset = id.trim_start_matches("note-")
+            .parse::<u64>()
+            .map(|timestamp| (timestamp % 100) as i32)
+            .unwrap_or(0) + 50;
+
+        StickerData {
+            id,
+            file_path,
+            x: 150 + offset,
+            y: 150 + offset,
+            width: 400,
+            height: 300,
+            background_color: "#FFFBEB".to_string(),
+            text_color: "#333333".to_string(),
+            mode: "edit".to_string(),
+            font_size: 12,
+            font_family: default_font_family(),
+            line_height: default_line_height(),
+            monitor_name: None,
+            monitor_position: None,
+            monitor_size: None,
+            monitor_scale_factor: None,
+            title: None,
+            tags: Vec::new(),
+            hidden: false,
+            group: None,
+            reminder: None,
+            content_title: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppState {
     pub windows: Vec<StickerData>,
+    #[serde(default)]
+    pub groups: Vec<NoteGroup>,
+}
+
+/// How a reminder repeats after it fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReminderRepeat {
+    #[default]
+    Never,
+    Daily,
+    /// Monday to Friday
+    Weekdays,
+}
+
+/// When a note reminds the user of itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    /// When it is next due, in milliseconds since the Unix epoch
+    pub at: i64,
+    #[serde(default)]
+    pub repeat: ReminderRepeat,
+}
+
+/// A named set of notes that are shown, hidden, recolored and arranged together
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteGroup {
+    pub id: String,
+    pub name: String,
+}
+
+/// Metadata stored as manifest.json inside an exported note bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteBundleManifest {
+    pub version: u32,
+    pub title: String,
+    pub background_color: String,
+    pub text_color: String,
+    #[serde(default = "default_font_size")]
+    pub font_size: u32,
+    #[serde(default = "default_font_family")]
+    pub font_family: String,
+    #[serde(default = "default_line_height")]
+    pub line_height: f64,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// Note creation time in milliseconds since the Unix epoch
+    #[serde(default)]
+    pub created_at: Option<u64>,
+}
+
+fn default_attachments_folder() -> String {
+    "attachments".to_string()
+}
+
+fn default_api_port() -> u16 {
+    48317
+}
+
+/// App settings stored as config.json in the data directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfig {
+    #[serde(default)]
+    pub vault: Option<VaultConfig>,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub hotkeys: HotkeyConfig,
+    #[serde(default)]
+    pub arrange: ArrangeConfig,
+}
+
+/// How notes are arranged on screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArrangeConfig {
+    /// Snap dragged notes to screen edges and to other notes
+    pub snap_while_dragging: bool,
+}
+
+impl Default for ArrangeConfig {
+    fn default() -> Self {
+        ArrangeConfig { snap_while_dragging: true }
+    }
+}
+
+/// System-wide shortcuts, as accelerators like "CmdOrCtrl+Shift+Alt+N".
+/// A missing binding gets its default; `null` turns it off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeyConfig {
+    pub new_note: Option<String>,
+    pub toggle_notes: Option<String>,
+    pub quick_capture: Option<String>,
+    /// Note that quick capture appends to, created on first use
+    pub inbox_note_id: Option<String>,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        HotkeyConfig {
+            new_note: Some("CmdOrCtrl+Shift+Alt+N".to_string()),
+            toggle_notes: Some("CmdOrCtrl+Shift+Alt+H".to_string()),
+            quick_capture: Some("CmdOrCtrl+Shift+Alt+Space".to_string()),
+            inbox_note_id: None,
+        }
+    }
+}
+
+/// The opt-in local HTTP API for other tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    /// Bearer token required on every request, generated when the API is first enabled
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        ApiConfig {
+            enabled: false,
+            port: default_api_port(),
+            token: String::new(),
+        }
+    }
+}
+
+/// An existing markdown folder (e.g. a team vault) used as the notes directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultConfig {
+    pub path: String,
+    /// Folder for pasted images: relative to the vault root,
+    /// or to the note's own folder when it starts with `./`
+    #[serde(default = "default_attachments_folder")]
+    pub attachments_folder: String,
+}
+
+/// A note as listed by the command line and the local API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSummary {
+    pub id: String,
+    pub title: Option<String>,
+    pub file_path: String,
+    pub background_color: String,
+    pub hidden: bool,
+    pub group: Option<String>,
 }
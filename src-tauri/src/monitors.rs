@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::Manager;
+use crate::commands::window::{find_note_data, save_window_state_impl};
+use crate::models::StickerData;
+use crate::placement::{connected_monitors, find_saved_monitor, monitor_relative_position, restored_position, validate_window_position, MonitorGeometry};
+use crate::window_manager::{is_note_window, mark_arranged, move_window};
+
+/// How often the connected monitors are checked; there is no event for monitors coming and going
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where a note was on a monitor, relative to the monitor's top-left corner in its logical pixels
+#[derive(Debug, Clone)]
+struct MonitorPlacement {
+    monitor: MonitorGeometry,
+    x: i32,
+    y: i32,
+}
+
+impl MonitorPlacement {
+    fn is_connected(&self, monitors: &[MonitorGeometry]) -> bool {
+        find_saved_monitor(self.monitor.name.as_deref(), self.monitor.position, self.monitor.size, monitors).is_some()
+    }
+
+    fn apply(&self, note: &mut StickerData) {
+        note.x = self.x;
+        note.y = self.y;
+        note.monitor_name = self.monitor.name.clone();
+        note.monitor_position = Some(self.monitor.position);
+        note.monitor_size = Some(self.monitor.size);
+        note.monitor_scale_factor = Some(self.monitor.scale_factor);
+    }
+}
+
+// Each note's placement at the last check, i.e. before its monitor was disconnected
+static LAST_PLACEMENTS: once_cell::sync::Lazy<Mutex<HashMap<String, MonitorPlacement>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Notes moved off a disconnected monitor, with where they go back to when it returns
+static RESCUED_NOTES: once_cell::sync::Lazy<Mutex<HashMap<String, MonitorPlacement>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn note_placements(app: &tauri::AppHandle) -> HashMap<String, MonitorPlacement> {
+    app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| is_note_window(label))
+        .filter_map(|(label, window)| {
+            let monitor = MonitorGeometry::from_monitor(&window.current_monitor().ok().flatten()?);
+            let position = window.outer_position().ok()?;
+            let (x, y) = monitor_relative_position((position.x, position.y), &monitor);
+            Some((label, MonitorPlacement { monitor, x, y }))
+        })
+        .collect()
+}
+
+/// A rescued note is saved with its placement on the disconnected monitor, so it also goes back there
+/// on a later launch with the monitor connected
+pub fn keep_rescued_placement(note: &mut StickerData) {
+    if let Some(placement) = RESCUED_NOTES.lock().unwrap().get(&note.id) {
+        placement.apply(note);
+    }
+}
+
+/// The user moved a note, so it stays where they put it when its monitor returns
+pub fn forget_rescued_note(label: &str) {
+    if RESCUED_NOTES.lock().unwrap().remove(label).is_some() {
+        println!("Note {} was moved, it stays off its original monitor", label);
+    }
+}
+
+/// Moves notes back to monitors that returned, and notes off monitors that are gone.
+/// Then switches to the layout saved for these monitors, if any.
+fn monitors_changed(app: &tauri::AppHandle, monitors: &[MonitorGeometry]) {
+    let returning: Vec<(String, MonitorPlacement)> = {
+        let mut rescued = RESCUED_NOTES.lock().unwrap();
+        let returning: Vec<(String, MonitorPlacement)> = rescued
+            .iter()
+            .filter(|(_, placement)| placement.is_connected(monitors))
+            .map(|(id, placement)| (id.clone(), placement.clone()))
+            .collect();
+        for (id, _) in &returning {
+            rescued.remove(id);
+        }
+        returning
+    };
+
+    for (id, placement) in returning {
+        let (Some(window), Some(mut note)) = (app.get_webview_window(&id), find_note_data(&id)) else {
+            continue;
+        };
+        placement.apply(&mut note);
+        let target = restored_position(&note, monitors);
+        println!("Monitor {:?} is back, returning note {}", placement.monitor.name, id);
+        mark_arranged(&id);
+        if let Err(e) = move_window(&window, &target, note.width, note.height) {
+            eprintln!("Failed to return note {}: {}", id, e);
+        }
+    }
+
+    let placements = LAST_PLACEMENTS.lock().unwrap().clone();
+    for (id, placement) in placements {
+        if placement.is_connected(monitors) || RESCUED_NOTES.lock().unwrap().contains_key(&id) {
+            continue;
+        }
+        let (Some(window), Some(note)) = (app.get_webview_window(&id), find_note_data(&id)) else {
+            continue;
+        };
+
+        // The system may have moved the window already; only move it if it is off-screen
+        if let Ok(position) = window.outer_position() {
+            let target = validate_window_position(position.x, position.y, note.width, note.height, monitors);
+            if (target.x, target.y) != (position.x, position.y) {
+                mark_arranged(&id);
+                if let Err(e) = move_window(&window, &target, note.width, note.height) {
+                    eprintln!("Failed to move note {} onto the screen: {}", id, e);
+                }
+            }
+        }
+        println!("Monitor {:?} is gone, rescued note {}", placement.monitor.name, id);
+        RESCUED_NOTES.lock().unwrap().insert(id, placement);
+    }
+
+    match crate::layouts::select_layout_for_monitors(app) {
+        Some(layout) => {
+            if let Err(e) = crate::layouts::apply_layout(app, &layout.name) {
+                eprintln!("Failed to apply layout: {}", e);
+            }
+        }
+        None => {
+            if let Err(e) = save_window_state_impl(app) {
+                eprintln!("Failed to save window state after monitor change: {}", e);
+            }
+            // No layout is active for these monitors any more
+            crate::menu::rebuild_menu(app);
+        }
+    }
+}
+
+/// Checks the connected monitors every few seconds and rearranges the notes when they change
+pub fn watch_monitors(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let mut known = connected_monitors(&app);
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+
+            let monitors = connected_monitors(&app);
+            // No monitors is a passing state while the displays are reconfigured
+            if monitors.is_empty() {
+                continue;
+            }
+            if monitors == known {
+                *LAST_PLACEMENTS.lock().unwrap() = note_placements(&app);
+                continue;
+            }
+
+            println!("Monitors changed: {} connected", monitors.len());
+            monitors_changed(&app, &monitors);
+            known = monitors;
+            *LAST_PLACEMENTS.lock().unwrap() = note_placements(&app);
+        }
+    });
+}
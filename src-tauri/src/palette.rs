@@ -0,0 +1,160 @@
+/// Note background colors offered by the color picker (ColorPicker.svelte)
+pub const PALETTE: [(&str, &str); 18] = [
+    ("Red Tint", "#FEF2F2"),
+    ("Orange Tint", "#FFF7ED"),
+    ("Amber", "#FFFBEB"),
+    ("Yellow", "#FEFCE8"),
+    ("Lime", "#F7FEE7"),
+    ("Green Light", "#F0FDF4"),
+    ("Emerald", "#ECFDF5"),
+    ("Teal", "#F0FDFA"),
+    ("Cyan", "#ECFEFF"),
+    ("Sky Blue", "#F0F9FF"),
+    ("Blue", "#EEF6FF"),
+    ("Indigo", "#EEF2FF"),
+    ("Violet", "#F5F3FF"),
+    ("Purple", "#FAF5FF"),
+    ("Fuchsia", "#FDF4FF"),
+    ("Pink", "#FDF2F8"),
+    ("Rose", "#FFF1F2"),
+    ("Gray", "#E8E8E8"),
+];
+
+/// Common color names used by other sticky-note tools, mapped to a palette entry
+const NAMED_COLORS: [(&str, &str); 14] = [
+    ("red", "Red Tint"),
+    ("orange", "Orange Tint"),
+    ("yellow", "Yellow"),
+    ("green", "Green Light"),
+    ("lime", "Lime"),
+    ("teal", "Teal"),
+    ("cyan", "Cyan"),
+    ("blue", "Blue"),
+    ("indigo", "Indigo"),
+    ("violet", "Violet"),
+    ("purple", "Purple"),
+    ("pink", "Pink"),
+    ("gray", "Gray"),
+    ("grey", "Gray"),
+];
+
+fn palette_hex(name: &str) -> Option<&'static str> {
+    PALETTE.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, hex)| *hex)
+}
+
+/// Parses `#RGB`, `#RRGGBB` or `#RRGGBBAA` (alpha ignored) into RGB components
+pub fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let digits = value.trim().strip_prefix('#')?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match digits.len() {
+        3 => {
+            let expand = |i: usize| channel(&digits[i..i + 1].repeat(2));
+            Some((expand(0)?, expand(1)?, expand(2)?))
+        }
+        6 | 8 => Some((channel(&digits[0..2])?, channel(&digits[2..4])?, channel(&digits[4..6])?)),
+        _ => None,
+    }
+}
+
+/// Maps a color from another tool onto the closest palette color.
+/// Accepts palette names, common color names ("yellow", "Blue") and hex values.
+/// Other tools use saturated colors, so hex values are matched by hue rather than exact RGB distance.
+pub fn map_to_palette(color: &str) -> Option<&'static str> {
+    let trimmed = color.trim();
+
+    if let Some(hex) = palette_hex(trimmed) {
+        return Some(hex);
+    }
+    let lower = trimmed.to_ascii_lowercase();
+    if let Some((_, palette_name)) = NAMED_COLORS.iter().find(|(name, _)| lower.contains(name)) {
+        return palette_hex(palette_name);
+    }
+
+    let (r, g, b) = parse_hex_color(trimmed)?;
+    if let Some((_, hex)) = PALETTE.iter().find(|(_, hex)| hex.eq_ignore_ascii_case(trimmed)) {
+        return Some(hex);
+    }
+
+    let (hue, saturation) = hue_and_saturation(r, g, b);
+    if saturation < 0.12 {
+        return palette_hex("Gray");
+    }
+
+    PALETTE
+        .iter()
+        .filter(|(name, _)| *name != "Gray")
+        .filter_map(|(_, hex)| {
+            let (pr, pg, pb) = parse_hex_color(hex)?;
+            let (palette_hue, _) = hue_and_saturation(pr, pg, pb);
+            let distance = (hue - palette_hue).abs();
+            Some((hex, distance.min(360.0 - distance)))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(hex, _)| *hex)
+}
+
+/// A note background color from user input: exact hex colors are kept, color names are mapped
+/// onto the palette
+pub fn resolve_note_color(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    let digits = trimmed.strip_prefix('#').unwrap_or("");
+    if matches!(digits.len(), 3 | 6) && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(trimmed.to_string())
+    } else {
+        map_to_palette(trimmed).map(|hex| hex.to_string())
+    }
+}
+
+/// Hue in degrees (0..360) and HSL saturation (0..1)
+fn hue_and_saturation(r: u8, g: u8, b: u8) -> (f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let lightness = (max + min) / 2.0;
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * (((g - b) / delta).rem_euclid(6.0))
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, saturation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_and_color_names_map_to_the_palette() {
+        assert_eq!(map_to_palette("amber"), Some("#FFFBEB"));
+        assert_eq!(map_to_palette(" Blue "), Some("#EEF6FF"));
+        assert_eq!(map_to_palette("LightYellow"), Some("#FEFCE8"));
+        assert_eq!(map_to_palette("grey"), Some("#E8E8E8"));
+    }
+
+    #[test]
+    fn hex_colors_map_by_hue() {
+        assert_eq!(map_to_palette("#fef2f2"), Some("#FEF2F2"));
+        assert_eq!(map_to_palette("#FF0000"), Some("#FEF2F2"));
+        assert_eq!(map_to_palette("#0F0"), Some("#F0FDF4"));
+        assert_eq!(map_to_palette("#808080"), Some("#E8E8E8"));
+    }
+
+    #[test]
+    fn unknown_colors_are_rejected() {
+        assert_eq!(map_to_palette(""), None);
+        assert_eq!(map_to_palette("#12"), None);
+        assert_eq!(map_to_palette("#GGHHII"), None);
+        assert_eq!(map_to_palette("chartreuse-ish"), None);
+    }
+}
@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+use tauri::Monitor;
+use crate::models::StickerData;
+
+/// Where a relocated note goes on the primary monitor, in logical pixels from its top-left corner
+const RELOCATE_OFFSET: f64 = 100.0;
+/// Space kept between a relocated note and the primary monitor's edges
+const RELOCATE_MARGIN: f64 = 50.0;
+
+/// A monitor's frame in physical desktop pixels, and its scale factor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorGeometry {
+    pub name: Option<String>,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    /// Missing in layouts saved before it was recorded
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: f64,
+}
+
+fn default_scale_factor() -> f64 {
+    1.0
+}
+
+impl MonitorGeometry {
+    pub fn from_monitor(monitor: &Monitor) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        MonitorGeometry {
+            name: monitor.name().cloned(),
+            position: (position.x, position.y),
+            size: (size.width, size.height),
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+
+    fn logical_size(&self) -> (f64, f64) {
+        (self.size.0 as f64 / self.scale_factor, self.size.1 as f64 / self.scale_factor)
+    }
+
+    /// Placement of a point given in this monitor's logical pixels, relative to its top-left corner
+    fn place(&self, x: f64, y: f64) -> Placement {
+        Placement {
+            x: self.position.0 + (x * self.scale_factor).round() as i32,
+            y: self.position.1 + (y * self.scale_factor).round() as i32,
+            scale_factor: self.scale_factor,
+        }
+    }
+
+    /// Whether any part of a window at a physical position, with a logical size, would be on this monitor
+    fn overlaps(&self, x: i32, y: i32, width: u32, height: u32) -> bool {
+        let right = x + (width as f64 * self.scale_factor).round() as i32;
+        let bottom = y + (height as f64 * self.scale_factor).round() as i32;
+        x < self.position.0 + self.size.0 as i32 && right > self.position.0 &&
+        y < self.position.1 + self.size.1 as i32 && bottom > self.position.1
+    }
+}
+
+/// Where to put a window: its top-left corner in physical desktop pixels,
+/// and the scale factor of the monitor it lands on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+}
+
+/// A window's physical position as an offset from its monitor's top-left corner, in the monitor's logical pixels
+pub fn monitor_relative_position(position: (i32, i32), monitor: &MonitorGeometry) -> (i32, i32) {
+    (
+        ((position.0 - monitor.position.0) as f64 / monitor.scale_factor).round() as i32,
+        ((position.1 - monitor.position.1) as f64 / monitor.scale_factor).round() as i32,
+    )
+}
+
+/// The connected monitors, left to right
+pub fn connected_monitors(app: &tauri::AppHandle) -> Vec<MonitorGeometry> {
+    let mut monitors: Vec<MonitorGeometry> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorGeometry::from_monitor)
+        .collect();
+    monitors.sort_by_key(|monitor| monitor.position);
+    monitors
+}
+
+/// Finds the connected monitor a note was saved on: by name, then by position and size
+pub fn find_saved_monitor<'a>(
+    name: Option<&str>,
+    position: (i32, i32),
+    size: (u32, u32),
+    monitors: &'a [MonitorGeometry],
+) -> Option<&'a MonitorGeometry> {
+    monitors
+        .iter()
+        .find(|monitor| name.is_some() && monitor.name.as_deref() == name)
+        .or_else(|| monitors.iter().find(|monitor| monitor.position == position && monitor.size == size))
+}
+
+/// The monitor at the desktop's origin, or the first one
+fn primary_monitor(monitors: &[MonitorGeometry]) -> Option<&MonitorGeometry> {
+    monitors.iter().find(|monitor| monitor.position == (0, 0)).or_else(|| monitors.first())
+}
+
+/// An offset along one axis that keeps the note on the monitor, or at its start if the note is larger
+fn clamp_offset(offset: f64, note_size: f64, monitor_size: f64) -> f64 {
+    offset.min(monitor_size - note_size).max(0.0)
+}
+
+/// Puts a note near the primary monitor's top-left corner, for notes whose monitor is gone
+fn relocate_to_primary(width: u32, height: u32, monitors: &[MonitorGeometry]) -> Placement {
+    let Some(primary) = primary_monitor(monitors) else {
+        println!("No monitors available, using default position");
+        return Placement { x: 100, y: 100, scale_factor: 1.0 };
+    };
+    let (monitor_width, monitor_height) = primary.logical_size();
+    let x = RELOCATE_OFFSET.min(monitor_width - width as f64 - RELOCATE_MARGIN).max(RELOCATE_MARGIN);
+    let y = RELOCATE_OFFSET.min(monitor_height - height as f64 - RELOCATE_MARGIN).max(RELOCATE_MARGIN);
+    let placement = primary.place(x, y);
+    println!("Relocated to primary monitor at ({}, {})", placement.x, placement.y);
+    placement
+}
+
+/// Keeps a window at a physical position if any part of it (with its logical size) is on a monitor,
+/// otherwise relocates it to the primary monitor
+pub fn validate_window_position(x: i32, y: i32, width: u32, height: u32, monitors: &[MonitorGeometry]) -> Placement {
+    match monitors.iter().find(|monitor| monitor.overlaps(x, y, width, height)) {
+        Some(monitor) => {
+            println!("Window position ({}, {}) is visible", x, y);
+            Placement { x, y, scale_factor: monitor.scale_factor }
+        }
+        None => {
+            println!("Window position ({}, {}) is NOT visible, relocating", x, y);
+            relocate_to_primary(width, height, monitors)
+        }
+    }
+}
+
+/// Where to restore a saved note.
+///
+/// Notes saved with their monitor's scale factor have `x`/`y` relative to that monitor in its logical
+/// pixels. If the monitor's logical size changed (another resolution or scaling), the note keeps its
+/// proportional place and is kept on the monitor. Notes whose monitor is gone go to the primary monitor.
+/// Older notes and notes never saved have absolute logical positions, checked against the monitors.
+pub fn restored_position(note: &StickerData, monitors: &[MonitorGeometry]) -> Placement {
+    let (Some(saved_position), Some(saved_size)) = (note.monitor_position, note.monitor_size) else {
+        // New or imported note, positioned in the primary monitor's logical pixels
+        let scale_factor = primary_monitor(monitors).map_or(1.0, |monitor| monitor.scale_factor);
+        return validate_window_position(
+            (note.x as f64 * scale_factor).round() as i32,
+            (note.y as f64 * scale_factor).round() as i32,
+            note.width,
+            note.height,
+            monitors,
+        );
+    };
+
+    let Some(monitor) = find_saved_monitor(note.monitor_name.as_deref(), saved_position, saved_size, monitors) else {
+        println!("No matching monitor found for {:?}, relocating to primary monitor", note.monitor_name);
+        return relocate_to_primary(note.width, note.height, monitors);
+    };
+
+    match note.monitor_scale_factor {
+        Some(saved_scale_factor) => {
+            let (monitor_width, monitor_height) = monitor.logical_size();
+            let saved_width = saved_size.0 as f64 / saved_scale_factor;
+            let saved_height = saved_size.1 as f64 / saved_scale_factor;
+            let x = if saved_width > 0.0 { note.x as f64 * monitor_width / saved_width } else { note.x as f64 };
+            let y = if saved_height > 0.0 { note.y as f64 * monitor_height / saved_height } else { note.y as f64 };
+            monitor.place(
+                clamp_offset(x, note.width as f64, monitor_width),
+                clamp_offset(y, note.height as f64, monitor_height),
+            )
+        }
+        // Saved before positions were monitor-relative: the window's physical position divided by the
+        // monitor's scale factor
+        None => validate_window_position(
+            (note.x as f64 * monitor.scale_factor).round() as i32,
+            (note.y as f64 * monitor.scale_factor).round() as i32,
+            note.width,
+            note.height,
+            monitors,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, position: (i32, i32), size: (u32, u32), scale_factor: f64) -> MonitorGeometry {
+        MonitorGeometry { name: Some(name.to_string()), position, size, scale_factor }
+    }
+
+    /// A 2x laptop screen with a 1x external monitor to its right
+    fn mixed_dpi() -> Vec<MonitorGeometry> {
+        vec![
+            monitor("Built-in", (0, 0), (2880, 1800), 2.0),
+            monitor("External", (2880, 0), (2560, 1440), 1.0),
+        ]
+    }
+
+    fn note_on(monitor: &MonitorGeometry, x: i32, y: i32) -> StickerData {
+        StickerData {
+            x,
+            y,
+            width: 400,
+            height: 300,
+            monitor_name: monitor.name.clone(),
+            monitor_position: Some(monitor.position),
+            monitor_size: Some(monitor.size),
+            monitor_scale_factor: Some(monitor.scale_factor),
+            ..StickerData::new_note("note-1".to_string(), "note-1.md".to_string())
+        }
+    }
+
+    #[test]
+    fn relative_position_uses_the_monitors_scale() {
+        let monitors = mixed_dpi();
+        assert_eq!(monitor_relative_position((200, 100), &monitors[0]), (100, 50));
+        assert_eq!(monitor_relative_position((3080, 100), &monitors[1]), (200, 100));
+    }
+
+    #[test]
+    fn saved_position_round_trips_on_each_monitor() {
+        let monitors = mixed_dpi();
+        for monitor in &monitors {
+            for physical in [(monitor.position.0 + 300, 240), (monitor.position.0 + 1000, 600)] {
+                let (x, y) = monitor_relative_position(physical, monitor);
+                let placement = restored_position(&note_on(monitor, x, y), &monitors);
+                assert_eq!((placement.x, placement.y), physical);
+                assert_eq!(placement.scale_factor, monitor.scale_factor);
+            }
+        }
+    }
+
+    #[test]
+    fn monitor_moved_in_the_desktop_keeps_the_relative_position() {
+        let saved = mixed_dpi();
+        let note = note_on(&saved[1], 200, 100);
+        // The external monitor is now arranged to the left of the laptop
+        let monitors = vec![
+            monitor("Built-in", (2560, 0), (2880, 1800), 2.0),
+            monitor("External", (0, 0), (2560, 1440), 1.0),
+        ];
+        let placement = restored_position(&note, &monitors);
+        assert_eq!((placement.x, placement.y), (200, 100));
+    }
+
+    #[test]
+    fn changed_resolution_keeps_the_proportional_position() {
+        let saved = monitor("External", (0, 0), (2560, 1440), 1.0);
+        let note = note_on(&saved, 1280, 720);
+        let monitors = vec![monitor("External", (0, 0), (1920, 1080), 1.0)];
+        let placement = restored_position(&note, &monitors);
+        assert_eq!((placement.x, placement.y), (960, 540));
+    }
+
+    #[test]
+    fn changed_scaling_keeps_the_proportional_position() {
+        let saved = monitor("Built-in", (0, 0), (2880, 1800), 2.0);
+        // Center of a 1440x900 logical screen, now scaled to 1920x1200
+        let note = note_on(&saved, 720, 450);
+        let monitors = vec![monitor("Built-in", (0, 0), (2880, 1800), 1.5)];
+        let placement = restored_position(&note, &monitors);
+        assert_eq!((placement.x, placement.y), (1440, 900));
+        assert_eq!(placement.scale_factor, 1.5);
+    }
+
+    #[test]
+    fn smaller_monitor_keeps_the_note_on_screen() {
+        let saved = monitor("External", (0, 0), (2560, 1440), 1.0);
+        let note = note_on(&saved, 2100, 1100);
+        let monitors = vec![monitor("External", (0, 0), (1280, 720), 1.0)];
+        let placement = restored_position(&note, &monitors);
+        assert!(placement.x + 400 <= 1280 && placement.y + 300 <= 720);
+    }
+
+    #[test]
+    fn missing_monitor_relocates_to_primary() {
+        let saved = mixed_dpi();
+        let note = note_on(&saved[1], 200, 100);
+        let monitors = vec![saved[0].clone()];
+        let placement = restored_position(&note, &monitors);
+        // 100 logical pixels from the laptop's corner, at its 2x scale
+        assert_eq!((placement.x, placement.y), (200, 200));
+        assert_eq!(placement.scale_factor, 2.0);
+    }
+
+    #[test]
+    fn monitor_without_name_matches_by_position_and_size() {
+        let saved = MonitorGeometry { name: None, position: (2880, 0), size: (2560, 1440), scale_factor: 1.0 };
+        let note = note_on(&saved, 200, 100);
+        let monitors = vec![
+            MonitorGeometry { name: None, ..mixed_dpi()[0].clone() },
+            saved.clone(),
+        ];
+        let placement = restored_position(&note, &monitors);
+        assert_eq!((placement.x, placement.y), (3080, 100));
+    }
+
+    #[test]
+    fn legacy_position_is_scaled_by_its_monitor() {
+        let monitors = mixed_dpi();
+        // Saved as physical / scale without the scale factor: (400, 200) physical on the laptop
+        let note = StickerData { monitor_scale_factor: None, ..note_on(&monitors[0], 200, 100) };
+        let placement = restored_position(&note, &monitors);
+        assert_eq!((placement.x, placement.y), (400, 200));
+    }
+
+    #[test]
+    fn note_without_monitor_uses_the_primary_scale() {
+        let monitors = mixed_dpi();
+        let note = StickerData { x: 250, y: 250, ..StickerData::new_note("note-1".to_string(), "note-1.md".to_string()) };
+        let placement = restored_position(&note, &monitors);
+        assert_eq!((placement.x, placement.y), (500, 500));
+    }
+
+    #[test]
+    fn offscreen_position_is_relocated() {
+        let monitors = mixed_dpi();
+        let placement = validate_window_position(-5000, -5000, 400, 300, &monitors);
+        assert_eq!((placement.x, placement.y), (200, 200));
+        // Partly visible windows stay where they are
+        let placement = validate_window_position(-200, 100, 400, 300, &monitors);
+        assert_eq!((placement.x, placement.y), (-200, 100));
+    }
+
+    #[test]
+    fn no_monitors_uses_a_default_position() {
+        let placement = restored_position(&note_on(&mixed_dpi()[0], 10, 10), &[]);
+        assert_eq!((placement.x, placement.y), (100, 100));
+    }
+}
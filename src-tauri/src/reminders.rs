@@ -0,0 +1,328 @@
+use std::thread;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use crate::commands::window::{note_summary, save_window_state_impl, WINDOW_METADATA};
+use crate::models::{Reminder, ReminderRepeat};
+
+/// How often due reminders are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// A reminder noticed longer than this after it was due was missed, e.g. while PeachLeaf was closed
+const MISSED_AFTER_MS: i64 = 60_000;
+
+/// Where the scheduler gets the time from, so it can be tested without waiting
+pub trait Clock {
+    type Tz: TimeZone;
+
+    /// The current time, in the time zone recurring reminders keep their time of day in
+    fn now(&self) -> DateTime<Self::Tz>;
+}
+
+/// The system clock, in the local time zone
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    type Tz = Local;
+
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A reminder that is due
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueReminder {
+    pub note_id: String,
+    pub reminder: Reminder,
+    /// Due over a minute ago. However many times a recurring reminder was missed, it fires once.
+    pub missed: bool,
+    /// What the reminder becomes after firing: its next occurrence, or None for a one-off reminder
+    pub next: Option<Reminder>,
+}
+
+/// A note's reminder as listed by the list_reminders command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderSummary {
+    pub note_id: String,
+    pub title: Option<String>,
+    pub reminder: Reminder,
+}
+
+/// The date at the given time of day. A time skipped by a daylight saving change moves an hour later.
+fn on_date<Tz: TimeZone>(tz: &Tz, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Tz>> {
+    let local = date.and_time(time);
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(local + chrono::Duration::hours(1))).earliest())
+}
+
+fn repeats_on(repeat: ReminderRepeat, date: NaiveDate) -> bool {
+    match repeat {
+        ReminderRepeat::Never => false,
+        ReminderRepeat::Daily => true,
+        ReminderRepeat::Weekdays => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+    }
+}
+
+/// The first occurrence of a recurring reminder after `now`, at the same time of day as before.
+/// None for a one-off reminder.
+pub fn next_occurrence<Tz: TimeZone>(reminder: &Reminder, now: &DateTime<Tz>) -> Option<Reminder> {
+    if reminder.repeat == ReminderRepeat::Never {
+        return None;
+    }
+
+    let tz = now.timezone();
+    let at = tz.timestamp_millis_opt(reminder.at).single()?;
+    let time = at.naive_local().time();
+    // Occurrences missed while PeachLeaf was closed for days are skipped, not counted
+    let mut date = at.date_naive().max(now.date_naive().pred_opt()?);
+    loop {
+        if repeats_on(reminder.repeat, date) {
+            if let Some(next) = on_date(&tz, date, time) {
+                if next > *now && next.timestamp_millis() > reminder.at {
+                    return Some(Reminder { at: next.timestamp_millis(), repeat: reminder.repeat });
+                }
+            }
+        }
+        date = date.succ_opt()?;
+    }
+}
+
+/// The reminders due at the clock's time, each with what it becomes after firing
+pub fn due_reminders<C: Clock>(clock: &C, reminders: &[(String, Reminder)]) -> Vec<DueReminder> {
+    let now = clock.now();
+    let now_millis = now.timestamp_millis();
+    reminders
+        .iter()
+        .filter(|(_, reminder)| reminder.at <= now_millis)
+        .map(|(note_id, reminder)| DueReminder {
+            note_id: note_id.clone(),
+            reminder: reminder.clone(),
+            missed: now_millis - reminder.at > MISSED_AFTER_MS,
+            next: next_occurrence(reminder, &now),
+        })
+        .collect()
+}
+
+/// A reminder set by the user. A one-off reminder has to be in the future; a recurring one that
+/// starts in the past begins at its next occurrence.
+pub fn new_reminder<C: Clock>(clock: &C, at: i64, repeat: ReminderRepeat) -> Result<Reminder, String> {
+    let now = clock.now();
+    let reminder = Reminder { at, repeat };
+    if at > now.timestamp_millis() {
+        return Ok(reminder);
+    }
+    next_occurrence(&reminder, &now).ok_or_else(|| "Reminder time is in the past".to_string())
+}
+
+/// Reminders of the open notes, soonest first
+pub fn list_reminders() -> Vec<ReminderSummary> {
+    let mut reminders: Vec<ReminderSummary> = WINDOW_METADATA
+        .lock()
+        .unwrap()
+        .values()
+        .filter_map(|note| {
+            let reminder = note.reminder.clone()?;
+            Some(ReminderSummary { note_id: note.id.clone(), title: note_summary(note).title, reminder })
+        })
+        .collect();
+    reminders.sort_by_key(|summary| (summary.reminder.at, summary.note_id.clone()));
+    reminders
+}
+
+/// Sets a note's reminder, or clears it with `None`
+pub fn set_reminder(app: &tauri::AppHandle, note_id: &str, reminder: Option<Reminder>) -> Result<(), String> {
+    println!("Setting reminder of note {} to {:?}", note_id, reminder);
+    {
+        let mut metadata = WINDOW_METADATA.lock().unwrap();
+        let note = metadata.get_mut(note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+        note.reminder = reminder;
+    }
+    save_window_state_impl(app)
+}
+
+/// Unpins a note that a reminder brought to the front
+pub fn dismiss_reminder(app: &tauri::AppHandle, note_id: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(note_id)
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+    window.set_always_on_top(false).map_err(|e| e.to_string())
+}
+
+fn notification_body(due: &DueReminder) -> String {
+    if !due.missed {
+        return "Reminder".to_string();
+    }
+    match Local.timestamp_millis_opt(due.reminder.at).single() {
+        Some(at) => format!("Missed reminder from {}", at.format("%b %-d, %H:%M")),
+        None => "Missed reminder".to_string(),
+    }
+}
+
+/// Shows a notification for a due reminder and brings its note to the front, pinned on top until dismissed
+fn fire_reminder(app: &tauri::AppHandle, due: &DueReminder) {
+    let title = WINDOW_METADATA
+        .lock()
+        .unwrap()
+        .get(&due.note_id)
+        .and_then(|note| note_summary(note).title)
+        .unwrap_or_else(|| "PeachLeaf".to_string());
+    if let Err(e) = app.notification().builder().title(title).body(notification_body(due)).show() {
+        eprintln!("Failed to show reminder notification: {}", e);
+    }
+
+    if let Some(window) = app.get_webview_window(&due.note_id) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        if let Err(e) = window.set_always_on_top(true) {
+            eprintln!("Failed to pin note {}: {}", due.note_id, e);
+        }
+        let _ = window.set_focus();
+    }
+    crate::commands::window::refresh_note_lists(app);
+
+    let payload = serde_json::json!({ "at": due.reminder.at, "missed": due.missed, "next": due.next });
+    if let Err(e) = app.emit_to(due.note_id.as_str(), "reminder-fired", payload) {
+        eprintln!("Failed to emit reminder-fired event: {}", e);
+    }
+}
+
+/// Fires the reminders due at the clock's time and moves recurring ones to their next occurrence.
+/// Returns how many fired.
+pub fn check_reminders<C: Clock>(app: &tauri::AppHandle, clock: &C) -> usize {
+    let reminders: Vec<(String, Reminder)> = WINDOW_METADATA
+        .lock()
+        .unwrap()
+        .values()
+        .filter_map(|note| note.reminder.clone().map(|reminder| (note.id.clone(), reminder)))
+        .collect();
+    let due = due_reminders(clock, &reminders);
+    if due.is_empty() {
+        return 0;
+    }
+
+    {
+        let mut metadata = WINDOW_METADATA.lock().unwrap();
+        for due in &due {
+            // Unless the reminder was changed in the meantime
+            if let Some(note) = metadata.get_mut(&due.note_id).filter(|note| note.reminder.as_ref() == Some(&due.reminder)) {
+                note.reminder = due.next.clone();
+            }
+        }
+    }
+    // Saved before notifying, so a reminder doesn't fire again if PeachLeaf quits right after
+    if let Err(e) = save_window_state_impl(app) {
+        eprintln!("Failed to save window state after reminders: {}", e);
+    }
+
+    for due in &due {
+        println!("Reminder of note {} is due (missed: {}, next: {:?})", due.note_id, due.missed, due.next);
+        fire_reminder(app, due);
+    }
+    due.len()
+}
+
+/// Checks for due reminders every few seconds. The first check also catches up on reminders that
+/// were missed while PeachLeaf was closed: each fires once.
+pub fn watch_reminders(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+        check_reminders(&app, &SystemClock);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    /// A clock stopped at a time in UTC+9
+    struct FixedClock(DateTime<FixedOffset>);
+
+    impl Clock for FixedClock {
+        type Tz = FixedOffset;
+
+        fn now(&self) -> DateTime<FixedOffset> {
+            self.0
+        }
+    }
+
+    fn time(date: (i32, u32, u32), hour: u32, minute: u32) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(date.0, date.1, date.2, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn reminder(at: DateTime<FixedOffset>, repeat: ReminderRepeat) -> (String, Reminder) {
+        ("note-1".to_string(), Reminder { at: at.timestamp_millis(), repeat })
+    }
+
+    // 2026-10-16 is a Friday
+    const FRIDAY: (i32, u32, u32) = (2026, 10, 16);
+
+    #[test]
+    fn reminder_is_not_due_before_its_time() {
+        let clock = FixedClock(time(FRIDAY, 14, 59));
+        assert!(due_reminders(&clock, &[reminder(time(FRIDAY, 15, 0), ReminderRepeat::Never)]).is_empty());
+    }
+
+    #[test]
+    fn one_off_reminder_fires_once_and_is_cleared() {
+        let clock = FixedClock(time(FRIDAY, 15, 0));
+        let due = due_reminders(&clock, &[reminder(time(FRIDAY, 15, 0), ReminderRepeat::Never)]);
+        assert_eq!(due.len(), 1);
+        assert!(!due[0].missed);
+        assert_eq!(due[0].next, None);
+    }
+
+    #[test]
+    fn daily_reminder_moves_to_the_same_time_tomorrow() {
+        let clock = FixedClock(time(FRIDAY, 15, 0));
+        let due = due_reminders(&clock, &[reminder(time(FRIDAY, 15, 0), ReminderRepeat::Daily)]);
+        assert_eq!(due[0].next, Some(reminder(time((2026, 10, 17), 15, 0), ReminderRepeat::Daily).1));
+    }
+
+    #[test]
+    fn weekday_reminder_skips_the_weekend() {
+        let clock = FixedClock(time(FRIDAY, 15, 0));
+        let due = due_reminders(&clock, &[reminder(time(FRIDAY, 15, 0), ReminderRepeat::Weekdays)]);
+        assert_eq!(due[0].next, Some(reminder(time((2026, 10, 19), 15, 0), ReminderRepeat::Weekdays).1));
+    }
+
+    #[test]
+    fn reminders_missed_while_closed_fire_once() {
+        // PeachLeaf was closed from Friday morning until Tuesday noon
+        let clock = FixedClock(time((2026, 10, 20), 12, 0));
+        let due = due_reminders(&clock, &[
+            reminder(time(FRIDAY, 9, 0), ReminderRepeat::Daily),
+            reminder(time(FRIDAY, 9, 0), ReminderRepeat::Never),
+        ]);
+        assert_eq!(due.len(), 2);
+        assert!(due.iter().all(|due| due.missed));
+        assert_eq!(due[0].next, Some(reminder(time((2026, 10, 21), 9, 0), ReminderRepeat::Daily).1));
+        assert_eq!(due[1].next, None);
+    }
+
+    #[test]
+    fn missed_reminder_later_today_stays_today() {
+        let clock = FixedClock(time(FRIDAY, 10, 0));
+        let due = due_reminders(&clock, &[reminder(time((2026, 10, 12), 18, 0), ReminderRepeat::Daily)]);
+        assert!(due[0].missed);
+        assert_eq!(due[0].next, Some(reminder(time(FRIDAY, 18, 0), ReminderRepeat::Daily).1));
+    }
+
+    #[test]
+    fn new_reminder_in_the_past() {
+        let clock = FixedClock(time(FRIDAY, 16, 0));
+        let at = time(FRIDAY, 15, 0).timestamp_millis();
+        assert!(new_reminder(&clock, at, ReminderRepeat::Never).is_err());
+        assert_eq!(
+            new_reminder(&clock, at, ReminderRepeat::Weekdays),
+            Ok(reminder(time((2026, 10, 19), 15, 0), ReminderRepeat::Weekdays).1)
+        );
+    }
+}
@@ -1,15 +1,56 @@
 use std::fs;
-use std::path::PathBuf;
-use crate::models::{AppState, StickerData};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::models::{AppConfig, AppState};
+use crate::vault::{active_vault, attachments_dir, load_vault_state, relative_link, save_vault_state, untitled_note_path, vault_state_file_path};
 
-pub fn get_state_file_path() -> PathBuf {
+// config.json is read once and kept in memory; save_config updates both
+static CONFIG: once_cell::sync::Lazy<Mutex<Option<AppConfig>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+pub fn get_data_dir() -> PathBuf {
     let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home_dir).join(".peach-leaf").join("state.json")
+    PathBuf::from(home_dir).join(".peach-leaf")
+}
+
+pub fn get_config_file_path() -> PathBuf {
+    get_data_dir().join("config.json")
+}
+
+pub fn get_keybindings_file_path() -> PathBuf {
+    get_data_dir().join("keybindings.json")
+}
+
+pub fn get_state_file_path() -> PathBuf {
+    match active_vault() {
+        Some(vault) => vault_state_file_path(&vault),
+        None => get_data_dir().join("state.json"),
+    }
+}
+
+/// Named note layouts, kept beside state.json so each vault has its own
+pub fn get_layouts_file_path() -> PathBuf {
+    get_state_file_path().with_file_name("layouts.json")
 }
 
+/// The notes directory: the vault root in vault mode, otherwise `~/.peach-leaf/notes`
 pub fn get_notes_dir() -> PathBuf {
-    let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home_dir).join(".peach-leaf").join("notes")
+    match active_vault() {
+        Some(vault) => PathBuf::from(vault.path),
+        None => get_data_dir().join("notes"),
+    }
+}
+
+pub fn get_instance_lock_path() -> PathBuf {
+    get_data_dir().join("instance.lock")
+}
+
+pub fn get_instance_info_path() -> PathBuf {
+    get_data_dir().join("instance.json")
+}
+
+pub fn get_thumbnail_cache_dir() -> PathBuf {
+    get_data_dir().join("cache").join("thumbnails")
 }
 
 pub fn ensure_notes_dir() -> Result<PathBuf, String> {
@@ -18,8 +59,74 @@ pub fn ensure_notes_dir() -> Result<PathBuf, String> {
     Ok(notes_dir)
 }
 
-pub fn save_app_state(windows: Vec<StickerData>) -> Result<(), String> {
-    let state = AppState { windows };
+/// Path for a new note's file. Vault notes get readable `Untitled` names instead of their id.
+pub fn new_note_path(note_id: &str) -> Result<PathBuf, String> {
+    let notes_dir = ensure_notes_dir()?;
+    if active_vault().is_some() {
+        Ok(untitled_note_path(&notes_dir))
+    } else {
+        Ok(notes_dir.join(format!("{}.md", note_id)))
+    }
+}
+
+/// Folder for a note's images and the markdown link prefix that points at it: the vault's
+/// attachments folder in vault mode, otherwise `{note_name}_images/` next to the note
+pub fn note_images_dir(note_path: &Path) -> Result<(PathBuf, String), String> {
+    let note_dir = note_path.parent()
+        .ok_or_else(|| "Could not get note directory".to_string())?;
+    if let Some(vault) = active_vault() {
+        let images_dir = attachments_dir(&vault, note_path);
+        let link_prefix = relative_link(note_dir, &images_dir);
+        return Ok((images_dir, link_prefix));
+    }
+
+    let note_stem = note_path.file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| "Invalid note filename".to_string())?;
+    Ok((note_dir.join(format!("{}_images", note_stem)), format!("./{}_images", note_stem)))
+}
+
+pub fn load_config() -> AppConfig {
+    let mut cached = CONFIG.lock().unwrap();
+    if let Some(config) = cached.as_ref() {
+        return config.clone();
+    }
+
+    let config_path = get_config_file_path();
+    let config = match fs::read_to_string(&config_path) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            eprintln!("Failed to parse config JSON: {}", e);
+            AppConfig::default()
+        }),
+        Err(_) => AppConfig::default(),
+    };
+    *cached = Some(config.clone());
+    config
+}
+
+pub fn save_config(config: &AppConfig) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    let config_path = get_config_file_path();
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&config_path, json).map_err(|e| e.to_string())?;
+    // The config holds the local API token, so only the user may read it
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&config_path, fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    }
+    *CONFIG.lock().unwrap() = Some(config.clone());
+    log!("Config saved to: {:?}", config_path);
+    Ok(())
+}
+
+pub fn save_app_state(state: AppState) -> Result<(), String> {
+    if let Some(vault) = active_vault() {
+        return save_vault_state(&vault, state);
+    }
+
     let json = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
     let state_path = get_state_file_path();
 
@@ -29,16 +136,20 @@ pub fn save_app_state(windows: Vec<StickerData>) -> Result<(), String> {
     }
 
     fs::write(&state_path, json).map_err(|e| e.to_string())?;
-    println!("App state saved to: {:?}", state_path);
+    log!("App state saved to: {:?}", state_path);
     Ok(())
 }
 
 pub fn load_app_state() -> Result<AppState, String> {
+    if let Some(vault) = active_vault() {
+        return load_vault_state(&vault);
+    }
+
     let state_path = get_state_file_path();
 
     if !state_path.exists() {
-        println!("No saved state found at {:?}", state_path);
-        return Ok(AppState { windows: vec![] });
+        log!("No saved state found at {:?}", state_path);
+        return Ok(AppState { windows: vec![], groups: vec![] });
     }
 
     let json = fs::read_to_string(&state_path).map_err(|e| {
@@ -51,11 +162,17 @@ pub fn load_app_state() -> Result<AppState, String> {
         e.to_string()
     })?;
 
-    println!("App state loaded from: {:?} ({} windows)", state_path, state.windows.len());
+    log!("App state loaded from: {:?} ({} windows)", state_path, state.windows.len());
     Ok(state)
 }
 
 pub fn cleanup_orphaned_notes(state: &AppState) -> Result<(), String> {
+    // A vault is shared with other tools, so files without a window are never orphans
+    if active_vault().is_some() {
+        println!("Vault mode, skipping orphaned note cleanup");
+        return Ok(());
+    }
+
     let notes_dir = get_notes_dir();
 
     // Get all note IDs from state.json
@@ -64,6 +181,12 @@ pub fn cleanup_orphaned_notes(state: &AppState) -> Result<(), String> {
         .map(|w| w.id.clone())
         .collect();
 
+    // Note files referenced by path, so a note stored in the notes directory under another name is kept
+    let valid_paths: std::collections::HashSet<PathBuf> = state.windows
+        .iter()
+        .map(|w| PathBuf::from(&w.file_path))
+        .collect();
+
     println!("Valid note IDs from state.json: {:?}", valid_ids);
 
     // Read all .md files in notes directory
@@ -76,8 +199,8 @@ pub fn cleanup_orphaned_notes(state: &AppState) -> Result<(), String> {
                         if let Some(file_stem) = path.file_stem().and_then(|s| s.to_str()) {
                             let note_id = file_stem.to_string();
 
-                            // If this note ID is not in state.json, delete it
-                            if !valid_ids.contains(&note_id) {
+                            // If this note is not in state.json by ID or path, delete it
+                            if !valid_ids.contains(&note_id) && !valid_paths.contains(&path) {
                                 println!("Deleting orphaned note file: {:?}", path);
                                 if let Err(e) = fs::remove_file(&path) {
                                     eprintln!("Failed to delete orphaned note {}: {}", note_id, e);
@@ -0,0 +1,250 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+use crate::commands::window::{note_summary, WINDOW_METADATA};
+
+pub const TASKS_WINDOW_LABEL: &str = "all-tasks";
+
+/// A `- [ ]` or `- [x]` item in a note
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskItem {
+    pub note_id: String,
+    /// 1-based line number in the note's file
+    pub line: usize,
+    pub checked: bool,
+    /// The text after the checkbox, annotations included
+    pub text: String,
+    /// What is inside `@due(...)`, e.g. "2026-10-20"
+    pub due: Option<String>,
+    /// `#tag` annotations, without the #
+    pub tags: Vec<String>,
+}
+
+/// A note's tasks, as listed by the list_tasks command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTasks {
+    pub note_id: String,
+    pub title: Option<String>,
+    pub background_color: String,
+    pub tasks: Vec<TaskItem>,
+}
+
+/// Where a task line's checkbox is: the byte index of the character between the brackets,
+/// whether it is checked, and the text after it
+fn parse_task_line(line: &str) -> Option<(usize, bool, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+
+    // "-", "*" or "+" bullets, or "1." / "1)" numbers
+    let marker = match rest.chars().next()? {
+        '-' | '*' | '+' => 1,
+        c if c.is_ascii_digit() => {
+            let digits = rest.chars().take_while(char::is_ascii_digit).count();
+            match rest[digits..].chars().next()? {
+                '.' | ')' => digits + 1,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let rest = rest[marker..].strip_prefix(' ')?.trim_start_matches(' ');
+
+    let checked = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let text = &rest[3..];
+    if !text.is_empty() && !text.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let checkbox = line.len() - rest.len() + 1;
+    Some((checkbox, checked, text.trim()))
+}
+
+/// The date or text of a `@due(...)` annotation
+fn parse_due(text: &str) -> Option<String> {
+    let start = text.find("@due(")? + "@due(".len();
+    let end = text[start..].find(')')?;
+    Some(text[start..start + end].trim().to_string()).filter(|due| !due.is_empty())
+}
+
+/// `#tag` words; a # inside a word, a heading-like `##` and numbers such as `#12` are not tags
+fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let Some(tag) = word.strip_prefix('#') else {
+            continue;
+        };
+        let tag: String = tag
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'))
+            .collect();
+        if !tag.is_empty() && !tag.chars().all(|c| c.is_ascii_digit()) && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// The task items of a note's markdown, outside code blocks
+pub fn extract_tasks(note_id: &str, markdown: &str) -> Vec<TaskItem> {
+    let mut tasks = Vec::new();
+    // The fence that opened the current code block, e.g. "```"
+    let mut fence: Option<String> = None;
+
+    for (index, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let marker: String = trimmed.chars().take_while(|c| *c == '`' || *c == '~').collect();
+            match &fence {
+                Some(open) if marker.starts_with(open.as_str()) => fence = None,
+                Some(_) => {}
+                None => fence = Some(marker),
+            }
+            continue;
+        }
+        if fence.is_some() {
+            continue;
+        }
+
+        if let Some((_, checked, text)) = parse_task_line(line) {
+            tasks.push(TaskItem {
+                note_id: note_id.to_string(),
+                line: index + 1,
+                checked,
+                text: text.to_string(),
+                due: parse_due(text),
+                tags: parse_tags(text),
+            });
+        }
+    }
+    tasks
+}
+
+/// The markdown with the task on a line (1-based) checked or unchecked, every other byte unchanged.
+/// With `expected_text`, the line must still be that task, so a note edited since its tasks were
+/// listed is not changed in the wrong place.
+pub fn set_task_checked(markdown: &str, line: usize, checked: bool, expected_text: Option<&str>) -> Result<String, String> {
+    let mut offset = 0;
+    for (index, current) in markdown.split_inclusive('\n').enumerate() {
+        if index + 1 == line {
+            let (checkbox, _, text) =
+                parse_task_line(current.trim_end_matches(['\r', '\n'])).ok_or_else(|| format!("Line {} is not a task", line))?;
+            if expected_text.is_some_and(|expected| expected.trim() != text) {
+                return Err(format!("Task on line {} has changed", line));
+            }
+
+            let mut updated = String::with_capacity(markdown.len());
+            updated.push_str(&markdown[..offset + checkbox]);
+            updated.push(if checked { 'x' } else { ' ' });
+            updated.push_str(&markdown[offset + checkbox + 1..]);
+            return Ok(updated);
+        }
+        offset += current.len();
+    }
+    Err(format!("Line {} is past the end of the note", line))
+}
+
+/// Tasks of every open note that has any, by note id
+pub fn list_tasks() -> Vec<NoteTasks> {
+    let notes: Vec<_> = WINDOW_METADATA.lock().unwrap().values().cloned().collect();
+    let mut lists: Vec<NoteTasks> = notes
+        .iter()
+        .filter_map(|note| {
+            let markdown = fs::read_to_string(&note.file_path).ok()?;
+            let tasks = extract_tasks(&note.id, &markdown);
+            if tasks.is_empty() {
+                return None;
+            }
+            Some(NoteTasks {
+                note_id: note.id.clone(),
+                title: note_summary(note).title,
+                background_color: note.background_color.clone(),
+                tasks,
+            })
+        })
+        .collect();
+    lists.sort_by(|a, b| a.note_id.cmp(&b.note_id));
+    lists
+}
+
+/// Opens the All Tasks window, or brings it to the front
+pub fn open_tasks_window(app: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(TASKS_WINDOW_LABEL) {
+        window.show().map_err(|e| e.to_string())?;
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    WebviewWindowBuilder::new(app, TASKS_WINDOW_LABEL, WebviewUrl::default())
+        .title("All Tasks")
+        .inner_size(420.0, 560.0)
+        .center()
+        .resizable(true)
+        .focused(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "# Today\n\
+        - [ ] Call the bank @due(2026-10-20) #errands\n\
+        - [x] Water plants\n\
+        \n\
+        ```\n\
+        - [ ] not a task, it's code\n\
+        ```\n\
+        1. [X] Book flights #travel #errands\n\
+        \x20 * [ ] Nested #12 issue#3 #to-do\n\
+        - [] missing space\n\
+        -[ ] no space after the bullet\n";
+
+    #[test]
+    fn extracts_tasks_with_line_numbers() {
+        let tasks = extract_tasks("note-1", NOTE);
+        let lines: Vec<(usize, bool)> = tasks.iter().map(|task| (task.line, task.checked)).collect();
+        assert_eq!(lines, vec![(2, false), (3, true), (8, true), (9, false)]);
+        assert!(tasks.iter().all(|task| task.note_id == "note-1"));
+        assert_eq!(tasks[1].text, "Water plants");
+    }
+
+    #[test]
+    fn extracts_due_dates_and_tags() {
+        let tasks = extract_tasks("note-1", NOTE);
+        assert_eq!(tasks[0].due.as_deref(), Some("2026-10-20"));
+        assert_eq!(tasks[0].tags, vec!["errands"]);
+        assert_eq!(tasks[1].due, None);
+        assert_eq!(tasks[2].tags, vec!["travel", "errands"]);
+        assert_eq!(tasks[3].tags, vec!["to-do"]);
+    }
+
+    #[test]
+    fn toggles_only_the_checkbox() {
+        let toggled = set_task_checked(NOTE, 2, true, Some("Call the bank @due(2026-10-20) #errands")).unwrap();
+        assert_eq!(toggled, NOTE.replacen("- [ ] Call", "- [x] Call", 1));
+
+        let toggled = set_task_checked(NOTE, 9, true, None).unwrap();
+        assert_eq!(toggled, NOTE.replacen("* [ ] Nested", "* [x] Nested", 1));
+        assert_eq!(set_task_checked(&toggled, 9, false, None).unwrap(), NOTE);
+    }
+
+    #[test]
+    fn keeps_windows_line_endings() {
+        let note = "- [ ] one\r\n- [ ] two\r\n";
+        assert_eq!(set_task_checked(note, 2, true, Some("two")).unwrap(), "- [ ] one\r\n- [x] two\r\n");
+    }
+
+    #[test]
+    fn refuses_lines_that_changed() {
+        assert!(set_task_checked(NOTE, 1, true, None).is_err());
+        assert!(set_task_checked(NOTE, 2, true, Some("Call the plumber")).is_err());
+        assert!(set_task_checked(NOTE, 99, true, None).is_err());
+    }
+}
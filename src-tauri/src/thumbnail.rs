@@ -0,0 +1,144 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use image::{imageops::FilterType, ImageFormat};
+use crate::state::get_thumbnail_cache_dir;
+
+/// Widths (in physical pixels) that thumbnails are generated at.
+/// Requests are rounded up to the nearest bucket so one thumbnail serves many window sizes.
+const THUMBNAIL_WIDTHS: [u32; 4] = [320, 640, 960, 1280];
+
+/// Maximum total size of the thumbnail cache before old entries are evicted
+const MAX_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Returns the thumbnail bucket for a requested display width,
+/// or None if the request is larger than every bucket.
+fn bucket_for_width(requested_width: u32) -> Option<u32> {
+    THUMBNAIL_WIDTHS.iter().copied().find(|w| *w >= requested_width)
+}
+
+/// FNV-1a hash of the image bytes, used as the cache key.
+/// Any change to the source file yields a new key, so stale thumbnails are never served.
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Thumbnails keep the source's alpha-capable format; JPEG sources stay JPEG to keep them small
+fn thumbnail_format(source_path: &Path) -> (ImageFormat, &'static str, &'static str) {
+    match source_path.extension().and_then(|s| s.to_str()) {
+        Some("jpg") | Some("jpeg") => (ImageFormat::Jpeg, "jpg", "image/jpeg"),
+        _ => (ImageFormat::Png, "png", "image/png"),
+    }
+}
+
+/// Returns a thumbnail of `source_bytes` that is at most `requested_width` wide (rounded up to a bucket).
+/// Returns Ok(None) when the original should be served as-is, e.g. it is already small enough
+/// or the format cannot be decoded.
+pub fn get_or_create_thumbnail(
+    source_path: &Path,
+    source_bytes: &[u8],
+    requested_width: u32,
+) -> Result<Option<(Vec<u8>, &'static str)>, String> {
+    let target_width = match bucket_for_width(requested_width) {
+        Some(width) => width,
+        None => return Ok(None),
+    };
+
+    let (format, extension, mime_type) = thumbnail_format(source_path);
+    let cache_dir = get_thumbnail_cache_dir();
+    let cache_path = cache_dir.join(format!(
+        "{:016x}_{}.{}",
+        hash_bytes(source_bytes),
+        target_width,
+        extension
+    ));
+
+    // Cache hit: refresh the modification time so LRU eviction keeps it
+    if let Ok(bytes) = fs::read(&cache_path) {
+        touch(&cache_path);
+        println!("Thumbnail cache hit: {:?}", cache_path);
+        return Ok(Some((bytes, mime_type)));
+    }
+
+    let img = match image::load_from_memory(source_bytes) {
+        Ok(img) => img,
+        Err(e) => {
+            println!("Skipping thumbnail, could not decode image: {}", e);
+            return Ok(None);
+        }
+    };
+
+    if img.width() <= target_width {
+        return Ok(None);
+    }
+
+    let target_height = ((img.height() as u64 * target_width as u64) / img.width() as u64).max(1) as u32;
+    let thumbnail = img.resize(target_width, target_height, FilterType::Triangle);
+
+    let mut thumbnail_bytes = Vec::new();
+    thumbnail.write_to(Cursor::new(&mut thumbnail_bytes), format)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+
+    fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("Failed to create thumbnail cache directory: {}", e))?;
+    fs::write(&cache_path, &thumbnail_bytes)
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+    println!("Thumbnail created: {:?} ({}x{})", cache_path, thumbnail.width(), thumbnail.height());
+
+    if let Err(e) = evict_least_recently_used(&cache_dir, MAX_CACHE_BYTES) {
+        eprintln!("Failed to evict thumbnails: {}", e);
+    }
+
+    Ok(Some((thumbnail_bytes, mime_type)))
+}
+
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+/// Deletes the least recently used thumbnails until the cache fits in `max_bytes`
+fn evict_least_recently_used(cache_dir: &Path, max_bytes: u64) -> Result<(), String> {
+    let mut entries: Vec<(PathBuf, u64, SystemTime)> = fs::read_dir(cache_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((entry.path(), metadata.len(), modified))
+        })
+        .collect();
+
+    let mut total_bytes: u64 = entries.iter().map(|(_, len, _)| len).sum();
+    if total_bytes <= max_bytes {
+        return Ok(());
+    }
+
+    // Oldest first
+    entries.sort_by_key(|(_, _, modified)| *modified);
+
+    for (path, len, _) in entries {
+        if total_bytes <= max_bytes {
+            break;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                total_bytes -= len;
+                println!("Evicted thumbnail: {:?}", path);
+            }
+            Err(e) => eprintln!("Failed to evict thumbnail {:?}: {}", path, e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder};
+use tauri::tray::TrayIconBuilder;
+use crate::models::NoteSummary;
+
+const TRAY_ID: &str = "peachleaf-tray";
+const MAX_TITLE_CHARS: usize = 40;
+
+/// Prefix of the tray menu ids that focus a note, followed by the note id
+pub const FOCUS_NOTE_MENU_PREFIX: &str = "focus_note_";
+
+// Notes listed in the tray menu, to rebuild it only when the list changes
+static TRAY_NOTES: once_cell::sync::Lazy<Mutex<Option<Vec<(String, String)>>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+fn note_menu_label(note: &NoteSummary) -> String {
+    let title = note.title.as_deref().map(str::trim).filter(|title| !title.is_empty()).unwrap_or("Untitled");
+    let mut label: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    if title.chars().count() > MAX_TITLE_CHARS {
+        label.push('…');
+    }
+    if note.hidden {
+        label.push_str(" (hidden)");
+    }
+    label
+}
+
+/// Each note's id and menu label
+pub fn note_menu_entries(notes: &[NoteSummary]) -> Vec<(String, String)> {
+    notes.iter().map(|note| (note.id.clone(), note_menu_label(note))).collect()
+}
+
+/// New Note, one entry per note, Show All, Hide All and Quit.
+/// Items reuse the app menu's ids, so the app menu handler handles them.
+fn build_tray_menu(app: &tauri::AppHandle, notes: &[(String, String)]) -> tauri::Result<Menu<tauri::Wry>> {
+    let mut builder = MenuBuilder::new(app)
+        .item(&MenuItemBuilder::new("New Note").id("new_note").build(app)?)
+        .separator();
+
+    if notes.is_empty() {
+        builder = builder.item(&MenuItemBuilder::new("No Notes").id("tray_no_notes").enabled(false).build(app)?);
+    }
+    for (id, label) in notes {
+        builder = builder.item(&MenuItemBuilder::new(label).id(format!("{}{}", FOCUS_NOTE_MENU_PREFIX, id)).build(app)?);
+    }
+
+    builder
+        .separator()
+        .item(&MenuItemBuilder::new("Show All Notes").id("show_all").build(app)?)
+        .item(&MenuItemBuilder::new("Hide All Notes").id("hide_all").build(app)?)
+        .separator()
+        .item(&MenuItemBuilder::new("Quit PeachLeaf").id("quit_app").build(app)?)
+        .build()
+}
+
+/// Adds the tray icon, so the app stays reachable with every note closed or hidden
+/// (Linux has no global menu bar)
+pub fn create_tray(app: &tauri::AppHandle, notes: &[NoteSummary]) -> tauri::Result<()> {
+    let notes = note_menu_entries(notes);
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("PeachLeaf")
+        .menu(&build_tray_menu(app, &notes)?)
+        .show_menu_on_left_click(true);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    *TRAY_NOTES.lock().unwrap() = Some(notes);
+    Ok(())
+}
+
+/// Lists the given notes in the tray menu, rebuilding it only if titles or visibility changed
+pub fn update_tray_notes(app: &tauri::AppHandle, notes: &[NoteSummary]) {
+    let entries = note_menu_entries(notes);
+
+    {
+        let mut listed = TRAY_NOTES.lock().unwrap();
+        // No tray (yet), or nothing changed
+        if listed.as_ref().map_or(true, |listed| *listed == entries) {
+            return;
+        }
+        *listed = Some(entries.clone());
+    }
+
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app, &entries) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                eprintln!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Failed to build tray menu: {}", e),
+    }
+}
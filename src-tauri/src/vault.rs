@@ -0,0 +1,268 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::commands::import::collect_markdown_files;
+use crate::models::{AppState, NoteGroup, StickerData, VaultConfig};
+use crate::state::{get_data_dir, load_config};
+use crate::thumbnail::hash_bytes;
+
+/// Window state of a vault, keyed by each note's path relative to the vault root
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VaultState {
+    #[serde(default)]
+    notes: BTreeMap<String, VaultEntry>,
+    #[serde(default)]
+    groups: Vec<NoteGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultEntry {
+    #[serde(flatten)]
+    window: StickerData,
+    #[serde(default)]
+    file_id: Option<u64>,
+    #[serde(default)]
+    content_hash: Option<u64>,
+}
+
+/// What is known about a note file, used to find it again after another tool renames or moves it
+#[derive(Debug, Clone, Default, PartialEq)]
+struct NoteFingerprint {
+    file_id: Option<u64>,
+    content_hash: Option<u64>,
+}
+
+// Last known fingerprint of each open vault note, by note id
+static FINGERPRINTS: once_cell::sync::Lazy<Mutex<HashMap<String, NoteFingerprint>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The configured vault, if vault mode is on and its folder is available
+pub fn active_vault() -> Option<VaultConfig> {
+    let vault = load_config().vault?;
+    if Path::new(&vault.path).is_dir() {
+        Some(vault)
+    } else {
+        eprintln!("Vault folder {} not found, using the default notes folder", vault.path);
+        None
+    }
+}
+
+/// Window state for a vault lives in the data directory, never inside the vault itself
+pub fn vault_state_file_path(vault: &VaultConfig) -> PathBuf {
+    get_data_dir()
+        .join("vaults")
+        .join(format!("{:016x}", hash_bytes(vault.path.as_bytes())))
+        .join("state.json")
+}
+
+/// State key of a note: its `/`-separated path relative to the vault root,
+/// or the absolute path for linked files outside the vault
+pub fn relative_key(root: &Path, path: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+fn resolve_key(root: &Path, key: &str) -> PathBuf {
+    let path = Path::new(key);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(key)
+    }
+}
+
+#[cfg(unix)]
+fn file_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|metadata| metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+fn fingerprint(path: &Path) -> NoteFingerprint {
+    NoteFingerprint {
+        file_id: file_id(path),
+        content_hash: fs::read(path).ok().map(|bytes| hash_bytes(&bytes)),
+    }
+}
+
+/// Searches the vault for a note that is no longer at `original`.
+/// Renames keep the file id, rewrites by sync tools keep the content, and a move between
+/// folders keeps the file name; ambiguous content or name matches are not guessed.
+fn locate_moved_note(
+    root: &Path,
+    original: &Path,
+    fingerprint: &NoteFingerprint,
+    claimed: &HashSet<PathBuf>,
+) -> Option<PathBuf> {
+    let mut files = Vec::new();
+    if let Err(e) = collect_markdown_files(root, &mut files) {
+        eprintln!("Failed to scan vault: {}", e);
+        return None;
+    }
+    let candidates: Vec<PathBuf> = files.into_iter().filter(|path| !claimed.contains(path)).collect();
+
+    if let Some(id) = fingerprint.file_id {
+        if let Some(found) = candidates.iter().find(|path| file_id(path) == Some(id)) {
+            return Some(found.clone());
+        }
+    }
+
+    if let Some(hash) = fingerprint.content_hash {
+        let matches: Vec<&PathBuf> = candidates
+            .iter()
+            .filter(|path| fs::read(path).map(|bytes| hash_bytes(&bytes) == hash).unwrap_or(false))
+            .collect();
+        if let [found] = matches.as_slice() {
+            return Some((*found).clone());
+        }
+    }
+
+    let name = original.file_name()?;
+    let matches: Vec<&PathBuf> = candidates.iter().filter(|path| path.file_name() == Some(name)).collect();
+    match matches.as_slice() {
+        [found] => Some((*found).clone()),
+        _ => None,
+    }
+}
+
+/// Finds a note's file after it disappeared from `old_path` while the note was open.
+/// `claimed` are the files of other open notes, which are never taken over.
+pub fn relocate_note(note_id: &str, old_path: &Path, claimed: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let vault = active_vault()?;
+    let fingerprint = FINGERPRINTS.lock().unwrap().get(note_id).cloned().unwrap_or_default();
+    let found = locate_moved_note(Path::new(&vault.path), old_path, &fingerprint, claimed)?;
+    log!("Note {} moved from {:?} to {:?}", note_id, old_path, found);
+    Some(found)
+}
+
+pub fn save_vault_state(vault: &VaultConfig, state: AppState) -> Result<(), String> {
+    let root = PathBuf::from(&vault.path);
+    let mut fingerprints = FINGERPRINTS.lock().unwrap();
+    let mut notes = BTreeMap::new();
+
+    for window in state.windows {
+        let path = PathBuf::from(&window.file_path);
+        // A file that was moved away keeps its last fingerprint so it can be found on the next load
+        let fingerprint = if path.exists() {
+            let fingerprint = fingerprint(&path);
+            fingerprints.insert(window.id.clone(), fingerprint.clone());
+            fingerprint
+        } else {
+            fingerprints.get(&window.id).cloned().unwrap_or_default()
+        };
+
+        notes.insert(relative_key(&root, &path), VaultEntry {
+            window,
+            file_id: fingerprint.file_id,
+            content_hash: fingerprint.content_hash,
+        });
+    }
+
+    let json = serde_json::to_string_pretty(&VaultState { notes, groups: state.groups }).map_err(|e| e.to_string())?;
+    let state_path = vault_state_file_path(vault);
+    if let Some(parent) = state_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&state_path, json).map_err(|e| e.to_string())?;
+    log!("Vault state saved to: {:?}", state_path);
+    Ok(())
+}
+
+/// Loads the vault's windows, following notes that were renamed or moved since the last save.
+/// Notes whose file can no longer be found are dropped.
+pub fn load_vault_state(vault: &VaultConfig) -> Result<AppState, String> {
+    let state_path = vault_state_file_path(vault);
+    if !state_path.exists() {
+        log!("No saved vault state found at {:?}", state_path);
+        return Ok(AppState { windows: vec![], groups: vec![] });
+    }
+
+    let json = fs::read_to_string(&state_path).map_err(|e| e.to_string())?;
+    let state: VaultState = serde_json::from_str(&json).map_err(|e| {
+        eprintln!("Failed to parse vault state JSON: {}", e);
+        e.to_string()
+    })?;
+
+    let root = PathBuf::from(&vault.path);
+    let mut claimed: HashSet<PathBuf> = state.notes
+        .keys()
+        .map(|key| resolve_key(&root, key))
+        .filter(|path| path.exists())
+        .collect();
+
+    let mut fingerprints = FINGERPRINTS.lock().unwrap();
+    let mut windows = Vec::new();
+
+    for (key, entry) in state.notes {
+        let fingerprint = NoteFingerprint { file_id: entry.file_id, content_hash: entry.content_hash };
+        let mut path = resolve_key(&root, &key);
+
+        if !path.exists() {
+            match locate_moved_note(&root, &path, &fingerprint, &claimed) {
+                Some(found) => {
+                    log!("Note {} moved from {} to {:?}", entry.window.id, key, found);
+                    claimed.insert(found.clone());
+                    path = found;
+                }
+                None => {
+                    log!("Note file {} is no longer in the vault, skipping", key);
+                    continue;
+                }
+            }
+        }
+
+        let mut window = entry.window;
+        window.file_path = path.to_string_lossy().to_string();
+        fingerprints.insert(window.id.clone(), fingerprint);
+        windows.push(window);
+    }
+
+    log!("Vault state loaded from: {:?} ({} windows)", state_path, windows.len());
+    Ok(AppState { windows, groups: state.groups })
+}
+
+/// A free `Untitled.md`, `Untitled 1.md`, ... path in the vault root
+pub fn untitled_note_path(root: &Path) -> PathBuf {
+    let mut path = root.join("Untitled.md");
+    let mut counter = 1;
+    while path.exists() {
+        path = root.join(format!("Untitled {}.md", counter));
+        counter += 1;
+    }
+    path
+}
+
+/// Folder pasted images of a vault note are saved in
+pub fn attachments_dir(vault: &VaultConfig, note_path: &Path) -> PathBuf {
+    match vault.attachments_folder.strip_prefix("./") {
+        Some(subfolder) => note_path.parent().unwrap_or(Path::new(".")).join(subfolder),
+        None => Path::new(&vault.path).join(&vault.attachments_folder),
+    }
+}
+
+/// Relative markdown link from `from_dir` to `target`, always starting with `./` or `../`
+pub fn relative_link(from_dir: &Path, target: &Path) -> String {
+    let from: Vec<_> = from_dir.components().collect();
+    let to: Vec<_> = target.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(to[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()));
+    if parts.first().map(|part| part.as_str()) != Some("..") {
+        parts.insert(0, ".".to_string());
+    }
+    parts.join("/")
+}
@@ -1,67 +1,19 @@
-use tauri::{Manager, Emitter, WebviewWindowBuilder, WebviewUrl, Monitor};
+use tauri::{Manager, Emitter, WebviewWindowBuilder, WebviewUrl, Monitor, LogicalSize, PhysicalPosition, PhysicalSize};
+use std::collections::HashMap;
 use std::fs;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::arrange::{align_rects, cascade_rects, snap_rect, tile_rects, Alignment, Rect, CASCADE_STEP, SNAP_DISTANCE, TILE_GAP};
+use crate::markdown::note_title;
 use crate::models::StickerData;
-use crate::state::ensure_notes_dir;
-use crate::commands::window::{WINDOW_METADATA, save_window_state_impl};
-
-/// Validates if a window position is visible on any available monitor.
-/// If not visible, returns a position on the primary monitor.
-fn validate_window_position(
-    x: i32,
-    y: i32,
-    width: u32,
-    height: u32,
-    monitors: &[Monitor]
-) -> (i32, i32) {
-    // Check if window is visible on any monitor
-    let is_visible = monitors.iter().any(|monitor| {
-        let pos = monitor.position();
-        let size = monitor.size();
-
-        // Window is visible if at least part of it is within the monitor bounds
-        let window_right = x + width as i32;
-        let window_bottom = y + height as i32;
-        let monitor_right = pos.x + size.width as i32;
-        let monitor_bottom = pos.y + size.height as i32;
-
-        // Check for overlap
-        x < monitor_right && window_right > pos.x &&
-        y < monitor_bottom && window_bottom > pos.y
-    });
-
-    if is_visible {
-        println!("Window position ({}, {}) is visible", x, y);
-        return (x, y);
-    }
-
-    // Window is not visible - move to primary monitor
-    println!("Window position ({}, {}) is NOT visible, relocating", x, y);
-
-    if let Some(primary_monitor) = monitors.iter().find(|m| {
-        let pos = m.position();
-        pos.x == 0 && pos.y == 0
-    }).or_else(|| monitors.first()) {
-        let primary_pos = primary_monitor.position();
-        let primary_size = primary_monitor.size();
+use crate::placement::{connected_monitors, restored_position, Placement};
+use crate::state::{get_notes_dir, new_note_path};
+use crate::commands::window::{WINDOW_METADATA, refresh_note_lists, save_window_state_impl};
 
-        // Place window with some padding from top-left corner
-        let new_x = primary_pos.x + 100;
-        let new_y = primary_pos.y + 100;
-
-        // Make sure window fits within monitor
-        let max_x = primary_pos.x + primary_size.width as i32 - width as i32 - 50;
-        let max_y = primary_pos.y + primary_size.height as i32 - height as i32 - 50;
-
-        let final_x = new_x.min(max_x).max(primary_pos.x + 50);
-        let final_y = new_y.min(max_y).max(primary_pos.y + 50);
-
-        println!("Relocated to primary monitor at ({}, {})", final_x, final_y);
-        (final_x, final_y)
-    } else {
-        println!("No monitors available, using default position");
-        (100, 100)
-    }
+/// Whether a window shows a note, rather than the color picker or the quick capture box
+pub fn is_note_window(label: &str) -> bool {
+    label != "color-picker" && label != crate::hotkeys::QUICK_CAPTURE_LABEL && label != crate::tasks::TASKS_WINDOW_LABEL
 }
 
 pub fn create_main_window(app: &tauri::AppHandle) -> Result<(), tauri::Error> {
@@ -80,86 +32,37 @@ pub fn create_main_window(app: &tauri::AppHandle) -> Result<(), tauri::Error> {
     Ok(())
 }
 
-pub fn restore_window(app: &tauri::AppHandle, sticker_data: StickerData) {
+/// Moves a window to a physical position. Some platforms convert it with the window's own scale
+/// factor, so a window that started on a monitor with another scale is moved again once it is there.
+pub fn move_window(window: &tauri::WebviewWindow, placement: &Placement, width: u32, height: u32) -> Result<(), String> {
+    let position = PhysicalPosition::new(placement.x, placement.y);
+    window.set_position(position).map_err(|e| e.to_string())?;
+    if window.scale_factor().is_ok_and(|scale_factor| scale_factor != placement.scale_factor) {
+        window.set_position(position).map_err(|e| e.to_string())?;
+    }
+    // Logical sizes follow the monitor the window is on now
+    window
+        .set_size(LogicalSize::new(width as f64, height as f64))
+        .map_err(|e| e.to_string())
+}
+
+pub fn restore_window(app: &tauri::AppHandle, mut sticker_data: StickerData) {
     println!("Restoring window: {} at ({}, {})", sticker_data.id, sticker_data.x, sticker_data.y);
 
-    // Get available monitors
-    let available_monitors = app.available_monitors().unwrap_or_default();
-
-    // Calculate absolute position based on saved monitor info
-    let (abs_x, abs_y) = if let (Some(saved_monitor_pos), Some(saved_monitor_size)) =
-        (sticker_data.monitor_position, sticker_data.monitor_size) {
-
-        println!("Saved monitor info: name={:?}, pos=({}, {}), size=({}, {})",
-                 sticker_data.monitor_name, saved_monitor_pos.0, saved_monitor_pos.1,
-                 saved_monitor_size.0, saved_monitor_size.1);
-
-        // Try to find a matching monitor
-        let target_monitor = available_monitors.iter().find(|m| {
-            // First try to match by name if available
-            if let Some(ref saved_name) = sticker_data.monitor_name {
-                if let Some(current_name) = m.name() {
-                    if current_name == saved_name {
-                        return true;
-                    }
-                }
-            }
-            // Then try to match by position and size
-            let pos = m.position();
-            let size = m.size();
-            pos.x == saved_monitor_pos.0 && pos.y == saved_monitor_pos.1 &&
-            size.width == saved_monitor_size.0 && size.height == saved_monitor_size.1
-        });
-
-        if let Some(monitor) = target_monitor {
-            let monitor_pos = monitor.position();
-            println!("Found matching monitor at ({}, {})", monitor_pos.x, monitor_pos.y);
-            // Use absolute position (monitor position + relative window position)
-            (sticker_data.x, sticker_data.y)
-        } else {
-            println!("No matching monitor found, relocating to primary monitor");
-            // Monitor not found - move to primary monitor
-            if let Some(primary_monitor) = available_monitors.iter().find(|m| {
-                // Primary monitor is typically at (0, 0) on macOS
-                let pos = m.position();
-                pos.x == 0 && pos.y == 0
-            }).or_else(|| available_monitors.first()) {
-                let primary_pos = primary_monitor.position();
-                let primary_size = primary_monitor.size();
-
-                // Calculate position to center the window on primary monitor
-                let window_x = primary_pos.x + 100;
-                let window_y = primary_pos.y + 100;
-
-                // Make sure window is within monitor bounds
-                let max_x = primary_pos.x + primary_size.width as i32 - sticker_data.width as i32 - 50;
-                let max_y = primary_pos.y + primary_size.height as i32 - sticker_data.height as i32 - 50;
-
-                let final_x = window_x.min(max_x).max(primary_pos.x + 50);
-                let final_y = window_y.min(max_y).max(primary_pos.y + 50);
-
-                println!("Relocated to primary monitor at ({}, {})", final_x, final_y);
-                (final_x, final_y)
-            } else {
-                println!("No monitors available, using default position");
-                (100, 100)
-            }
-        }
-    } else {
-        println!("No monitor info saved, checking if position is visible");
-        // No saved monitor info - verify the window is within visible bounds
-        validate_window_position(sticker_data.x, sticker_data.y,
-                                 sticker_data.width, sticker_data.height,
-                                 &available_monitors)
-    };
+    let placement = restored_position(&sticker_data, &connected_monitors(app));
+
+    // Placing the window is not a drag, so don't snap it
+    mark_arranged(&sticker_data.id);
 
     // Populate WINDOW_METADATA with the restored window's data
+    sticker_data.content_title = fs::read_to_string(&sticker_data.file_path).ok().and_then(|markdown| note_title(&markdown));
     {
         let mut metadata = WINDOW_METADATA.lock().unwrap();
         metadata.insert(sticker_data.id.clone(), sticker_data.clone());
         println!("Populated metadata for window {}: color={}, mode={}", sticker_data.id, sticker_data.background_color, sticker_data.mode);
     }
 
+    // Created hidden and then moved, as the builder only takes logical positions
     match WebviewWindowBuilder::new(
         app,
         &sticker_data.id,
@@ -167,19 +70,28 @@ pub fn restore_window(app: &tauri::AppHandle, sticker_data: StickerData) {
     )
     .title("PeachLeaf")
     .inner_size(sticker_data.width as f64, sticker_data.height as f64)
-    .position(abs_x as f64, abs_y as f64)
     .decorations(false)
     .resizable(true)
     .always_on_top(false)
+    .visible(false)
     .build() {
         Ok(window) => {
+            if let Err(e) = move_window(&window, &placement, sticker_data.width, sticker_data.height) {
+                eprintln!("Failed to position window {}: {}", sticker_data.id, e);
+            }
+            if !sticker_data.hidden {
+                if let Err(e) = window.show() {
+                    eprintln!("Failed to show window {}: {}", sticker_data.id, e);
+                }
+            }
+
             // Send sticker data to the window after it's created
             if let Err(e) = window.emit("init-sticker", &sticker_data) {
                 eprintln!("Failed to emit init-sticker event: {}", e);
             }
 
-            // Update font menu checks based on restored font size
-            crate::menu::update_font_menu_checks(sticker_data.font_size);
+            // Show the restored note's font in the Style menu
+            crate::menu::update_style_menu(app, sticker_data.font_size, &sticker_data.font_family, sticker_data.line_height);
 
             println!("Window {} restored successfully", sticker_data.id);
         }
@@ -189,6 +101,81 @@ pub fn restore_window(app: &tauri::AppHandle, sticker_data: StickerData) {
     }
 }
 
+/// Generates a `note-{millis}` id that is not yet used by a window or a note file
+pub fn generate_note_id(app: &tauri::AppHandle) -> String {
+    next_free_note_id(|id| app.get_webview_window(id).is_some())
+}
+
+/// Generates a `note-{millis}` id that is neither `is_taken` nor used by a note file
+pub fn next_free_note_id(is_taken: impl Fn(&str) -> bool) -> String {
+    let mut timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    let notes_dir = get_notes_dir();
+
+    loop {
+        let id = format!("note-{}", timestamp);
+        if !is_taken(&id) && !notes_dir.join(format!("{}.md", id)).exists() {
+            return id;
+        }
+        timestamp += 1;
+    }
+}
+
+/// Opens a window for a note whose file has already been written (e.g. an imported note)
+/// and persists it to state.json
+pub fn open_note_window(app: &tauri::AppHandle, sticker_data: StickerData) {
+    restore_window(app, sticker_data);
+    refresh_note_lists(app);
+
+    if let Err(e) = save_window_state_impl(app) {
+        eprintln!("Failed to save window state after opening note: {}", e);
+    }
+}
+
+/// Creates a note with the given content and background color, opens its window and persists it.
+/// Returns the new note's id.
+pub fn create_note(app: &tauri::AppHandle, content: &str, background_color: Option<String>) -> Result<String, String> {
+    let note_id = generate_note_id(app);
+    let file_path = new_note_path(&note_id)?;
+    fs::write(&file_path, content).map_err(|e| format!("Failed to create note: {}", e))?;
+
+    let mut sticker_data = StickerData::new_note(note_id.clone(), file_path.to_string_lossy().to_string());
+    if let Some(color) = background_color {
+        sticker_data.background_color = color;
+    }
+    open_note_window(app, sticker_data);
+
+    Ok(note_id)
+}
+
+/// Shows every hidden note window and persists their visibility
+pub fn show_all_notes(app: &tauri::AppHandle) {
+    for window in app.webview_windows().values() {
+        if is_note_window(window.label()) {
+            let _ = window.show();
+        }
+    }
+    refresh_note_lists(app);
+    if let Err(e) = save_window_state_impl(app) {
+        eprintln!("Failed to save window state after showing notes: {}", e);
+    }
+}
+
+/// Hides every note window and persists their visibility
+pub fn hide_all_notes(app: &tauri::AppHandle) {
+    for window in app.webview_windows().values() {
+        if is_note_window(window.label()) {
+            let _ = window.hide();
+        }
+    }
+    refresh_note_lists(app);
+    if let Err(e) = save_window_state_impl(app) {
+        eprintln!("Failed to save window state after hiding notes: {}", e);
+    }
+}
+
 pub fn create_new_note_backend(app: &tauri::AppHandle) {
     // If no windows exist, create main window
     if app.webview_windows().is_empty() {
@@ -199,75 +186,271 @@ pub fn create_new_note_backend(app: &tauri::AppHandle) {
         return;
     }
 
-    // Generate unique ID
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis();
-    let new_id = format!("note-{}", timestamp);
-
-    // Use permanent directory for notes
-    let notes_dir = match ensure_notes_dir() {
-        Ok(dir) => dir,
-        Err(e) => {
-            eprintln!("Failed to create notes directory: {}", e);
-            return;
+    // A note created from a note in a group joins that group
+    let group = focused_note_window(app).and_then(|window| crate::groups::note_group(window.label()));
+    match create_note(app, "", None) {
+        Ok(note_id) => {
+            if let Some(group) = group {
+                if let Err(e) = crate::groups::set_note_group(app, &note_id, Some(&group)) {
+                    eprintln!("Failed to add new note to group: {}", e);
+                }
+            }
         }
+        Err(e) => eprintln!("Failed to create new note: {}", e),
+    }
+}
+
+/// Whether dragged notes can snap: only where PeachLeaf can tell that the mouse button was released.
+/// Elsewhere (Linux) a note that pauses mid-drag would snap out from under the pointer.
+pub const CAN_SNAP_WHILE_DRAGGING: bool = cfg!(any(windows, target_os = "macos"));
+
+/// How long a note has to stop moving, with the mouse button released, before it snaps
+const SNAP_DELAY: Duration = Duration::from_millis(500);
+/// Move events arriving this soon after PeachLeaf moved a note itself are not snapped
+const ARRANGED_MOVE_GRACE: Duration = Duration::from_secs(1);
+
+// When PeachLeaf last moved each note itself (arranging, snapping, restoring)
+static ARRANGED_MOVES: once_cell::sync::Lazy<Mutex<HashMap<String, Instant>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Last move of each note that is being dragged; a note is in here while a thread waits to snap it
+static DRAGGED_NOTES: once_cell::sync::Lazy<Mutex<HashMap<String, Instant>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Marks a note as moved by PeachLeaf, so the resulting move events don't snap it
+pub fn mark_arranged(label: &str) {
+    ARRANGED_MOVES.lock().unwrap().insert(label.to_string(), Instant::now());
+}
+
+fn same_monitor(a: &Monitor, b: &Monitor) -> bool {
+    a.position() == b.position() && a.size() == b.size()
+}
+
+/// A monitor's work area (without menu bar, dock or taskbar) in its logical pixels,
+/// relative to the monitor's top-left corner
+fn monitor_work_area(monitor: &Monitor) -> Rect {
+    let scale = monitor.scale_factor();
+    let work_area = monitor.work_area();
+    Rect::new(
+        (work_area.position.x - monitor.position().x) as f64 / scale,
+        (work_area.position.y - monitor.position().y) as f64 / scale,
+        work_area.size.width as f64 / scale,
+        work_area.size.height as f64 / scale,
+    )
+}
+
+/// A window's frame in a monitor's logical pixels, relative to the monitor's top-left corner
+fn window_rect(window: &tauri::WebviewWindow, monitor: &Monitor) -> Result<Rect, String> {
+    let scale = monitor.scale_factor();
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    Ok(Rect::new(
+        (position.x - monitor.position().x) as f64 / scale,
+        (position.y - monitor.position().y) as f64 / scale,
+        size.width as f64 / scale,
+        size.height as f64 / scale,
+    ))
+}
+
+/// Moves and resizes a window to a frame given in a monitor's logical pixels
+fn set_window_rect(window: &tauri::WebviewWindow, monitor: &Monitor, rect: &Rect) -> Result<(), String> {
+    let scale = monitor.scale_factor();
+    mark_arranged(window.label());
+    window
+        .set_position(PhysicalPosition::new(
+            monitor.position().x + (rect.x * scale).round() as i32,
+            monitor.position().y + (rect.y * scale).round() as i32,
+        ))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_size(PhysicalSize::new((rect.width * scale).round() as u32, (rect.height * scale).round() as u32))
+        .map_err(|e| e.to_string())
+}
+
+fn focused_note_window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    app.webview_windows()
+        .into_values()
+        .find(|window| is_note_window(window.label()) && window.is_focused().unwrap_or(false))
+}
+
+/// The monitor to arrange: the focused note's, or the primary one
+fn arrange_monitor(app: &tauri::AppHandle) -> Option<Monitor> {
+    focused_note_window(app)
+        .and_then(|window| window.current_monitor().ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten())
+        .or_else(|| app.available_monitors().ok().and_then(|monitors| monitors.into_iter().next()))
+}
+
+/// Visible, non-minimized notes on a monitor, top to bottom and left to right.
+/// With a group, the group's visible notes on any monitor instead, measured in this monitor's pixels.
+fn visible_notes_on(app: &tauri::AppHandle, monitor: &Monitor, group: Option<&str>) -> Vec<(tauri::WebviewWindow, Rect)> {
+    let members = group.map(crate::groups::group_members);
+    let mut notes: Vec<(tauri::WebviewWindow, Rect)> = app
+        .webview_windows()
+        .into_values()
+        .filter(|window| is_note_window(window.label()))
+        .filter(|window| window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false))
+        .filter(|window| match &members {
+            Some(members) => members.iter().any(|id| id == window.label()),
+            None => window.current_monitor().ok().flatten().is_some_and(|current| same_monitor(&current, monitor)),
+        })
+        .filter_map(|window| window_rect(&window, monitor).ok().map(|rect| (window, rect)))
+        .collect();
+    notes.sort_by(|(a, a_rect), (b, b_rect)| {
+        a_rect.y.total_cmp(&b_rect.y)
+            .then(a_rect.x.total_cmp(&b_rect.x))
+            .then_with(|| a.label().cmp(b.label()))
+    });
+    notes
+}
+
+fn apply_rects(app: &tauri::AppHandle, monitor: &Monitor, windows: &[tauri::WebviewWindow], rects: &[Rect]) -> Result<usize, String> {
+    for (window, rect) in windows.iter().zip(rects) {
+        set_window_rect(window, monitor, rect)?;
+    }
+    save_window_state_impl(app)?;
+    Ok(windows.len())
+}
+
+/// Tiles the visible notes on the focused note's monitor into a grid, or a group's visible notes
+/// onto that monitor. Returns how many were moved.
+pub fn tile_notes(app: &tauri::AppHandle, group: Option<&str>) -> Result<usize, String> {
+    let monitor = arrange_monitor(app).ok_or_else(|| "No monitor available".to_string())?;
+    let (windows, _): (Vec<_>, Vec<_>) = visible_notes_on(app, &monitor, group).into_iter().unzip();
+    let rects = tile_rects(windows.len(), &monitor_work_area(&monitor), TILE_GAP);
+    apply_rects(app, &monitor, &windows, &rects)
+}
+
+/// Cascades the visible notes on the focused note's monitor from its top-left corner, or a group's
+/// visible notes onto that monitor. Returns how many were moved.
+pub fn cascade_notes(app: &tauri::AppHandle, group: Option<&str>) -> Result<usize, String> {
+    let monitor = arrange_monitor(app).ok_or_else(|| "No monitor available".to_string())?;
+    let (windows, rects): (Vec<_>, Vec<_>) = visible_notes_on(app, &monitor, group).into_iter().unzip();
+    let sizes: Vec<(f64, f64)> = rects.iter().map(|rect| (rect.width, rect.height)).collect();
+    let rects = cascade_rects(&sizes, &monitor_work_area(&monitor), CASCADE_STEP);
+    apply_rects(app, &monitor, &windows, &rects)
+}
+
+/// Lines up the given notes with an edge or center of the focused one (or the first given).
+/// Without ids, lines up the visible notes on the focused note's monitor. Returns how many were moved.
+pub fn align_notes(app: &tauri::AppHandle, note_ids: &[String], alignment: Alignment) -> Result<usize, String> {
+    let focused = focused_note_window(app);
+    let windows: Vec<tauri::WebviewWindow> = if note_ids.is_empty() {
+        let monitor = arrange_monitor(app).ok_or_else(|| "No monitor available".to_string())?;
+        visible_notes_on(app, &monitor, None).into_iter().map(|(window, _)| window).collect()
+    } else {
+        note_ids
+            .iter()
+            .map(|id| {
+                app.get_webview_window(id)
+                    .filter(|window| is_note_window(window.label()))
+                    .ok_or_else(|| format!("Note not found: {}", id))
+            })
+            .collect::<Result<_, _>>()?
     };
-    let file_path = notes_dir.join(format!("{}.md", new_id));
-    let file_path_str = file_path.to_string_lossy().to_string();
-
-    // Random offset for window position
-    let random_offset = (timestamp % 100) as i32 + 50;
-
-    // Create sticker data
-    let sticker_data = StickerData {
-        id: new_id.clone(),
-        file_path: file_path_str.clone(),
-        x: 150 + random_offset,
-        y: 150 + random_offset,
-        width: 400,
-        height: 300,
-        background_color: "#FFFBEB".to_string(),  // Amber - warm yellow color
-        text_color: "#333333".to_string(),
-        mode: "edit".to_string(),
-        font_size: 12,  // Default font size 12px
-        monitor_name: None,
-        monitor_position: None,
-        monitor_size: None,
+
+    let reference = focused
+        .filter(|focused| windows.iter().any(|window| window.label() == focused.label()))
+        .or_else(|| windows.first().cloned())
+        .ok_or_else(|| "No notes to align".to_string())?;
+    // Everything is measured in the reference note's monitor
+    let monitor = reference
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| arrange_monitor(app))
+        .ok_or_else(|| "No monitor available".to_string())?;
+
+    let rects = windows.iter().map(|window| window_rect(window, &monitor)).collect::<Result<Vec<_>, _>>()?;
+    let reference_rect = window_rect(&reference, &monitor)?;
+    let aligned = align_rects(&rects, alignment, &reference_rect);
+    apply_rects(app, &monitor, &windows, &aligned)
+}
+
+/// Snaps a note to its monitor's edges and to the notes next to it
+pub fn snap_note(app: &tauri::AppHandle, label: &str) -> Result<(), String> {
+    let window = app.get_webview_window(label).ok_or_else(|| format!("Note not found: {}", label))?;
+    let Some(monitor) = window.current_monitor().map_err(|e| e.to_string())? else {
+        return Ok(());
     };
 
-    // Write empty file
-    if let Err(e) = fs::write(&file_path, "") {
-        eprintln!("Failed to create file: {}", e);
+    let rect = window_rect(&window, &monitor)?;
+    let others: Vec<Rect> = visible_notes_on(app, &monitor, None)
+        .into_iter()
+        .filter(|(other, _)| other.label() != label)
+        .map(|(_, rect)| rect)
+        .collect();
+    let snapped = snap_rect(&rect, &others, &monitor_work_area(&monitor), SNAP_DISTANCE);
+
+    // Compare in physical pixels, so rounding doesn't keep nudging the note
+    let scale = monitor.scale_factor();
+    if ((snapped.x - rect.x) * scale).round() == 0.0 && ((snapped.y - rect.y) * scale).round() == 0.0 {
+        return Ok(());
+    }
+    println!("Snapping note {} to ({:.0}, {:.0})", label, snapped.x, snapped.y);
+    set_window_rect(&window, &monitor, &snapped)?;
+    save_window_state_impl(app)
+}
+
+/// Whether a mouse button is held down, i.e. a drag may still be going on even if the note stopped
+/// moving. Only used where `CAN_SNAP_WHILE_DRAGGING`.
+fn mouse_button_down() -> bool {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_LBUTTON, VK_RBUTTON};
+        // The high bit is set while the button is down; both are checked as they may be swapped
+        [VK_LBUTTON, VK_RBUTTON].iter().any(|&key| unsafe { GetAsyncKeyState(key as i32) } < 0)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let buttons: usize = unsafe { objc2::msg_send![objc2::class!(NSEvent), pressedMouseButtons] };
+        buttons != 0
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        true
+    }
+}
+
+/// Called for every move of a note window. A note the user moves stays off a reconnected monitor,
+/// and where `CAN_SNAP_WHILE_DRAGGING`, it snaps into place once the user has let go of it.
+pub fn note_moved(app: &tauri::AppHandle, label: &str) {
+    if !is_note_window(label) {
+        return;
+    }
+    let arranged_recently = ARRANGED_MOVES
+        .lock()
+        .unwrap()
+        .get(label)
+        .is_some_and(|moved_at| moved_at.elapsed() < ARRANGED_MOVE_GRACE);
+    if arranged_recently {
         return;
     }
 
-    // Create window
-    match WebviewWindowBuilder::new(
-        app,
-        &new_id,
-        WebviewUrl::default(),
-    )
-    .title("PeachLeaf")
-    .inner_size(400.0, 300.0)
-    .position((150 + random_offset) as f64, (150 + random_offset) as f64)
-    .decorations(false)
-    .resizable(true)
-    .build() {
-        Ok(window) => {
-            // Send sticker data to the window
-            if let Err(e) = window.emit("init-sticker", sticker_data) {
-                eprintln!("Failed to emit init-sticker: {}", e);
-            }
+    crate::monitors::forget_rescued_note(label);
+    if !CAN_SNAP_WHILE_DRAGGING || !crate::state::load_config().arrange.snap_while_dragging {
+        return;
+    }
+
+    // One waiting thread per dragged note
+    if DRAGGED_NOTES.lock().unwrap().insert(label.to_string(), Instant::now()).is_some() {
+        return;
+    }
 
-            // Save window state immediately after creating new window
-            if let Err(e) = save_window_state_impl(app) {
-                eprintln!("Failed to save window state after creating new note: {}", e);
+    let app = app.clone();
+    let label = label.to_string();
+    thread::spawn(move || {
+        loop {
+            thread::sleep(SNAP_DELAY);
+            let mut dragged = DRAGGED_NOTES.lock().unwrap();
+            let stopped = dragged.get(&label).map_or(true, |last_move| last_move.elapsed() >= SNAP_DELAY);
+            if stopped && !mouse_button_down() {
+                dragged.remove(&label);
+                break;
             }
         }
-        Err(e) => {
-            eprintln!("Failed to create window: {}", e);
+        if let Err(e) = snap_note(&app, &label) {
+            eprintln!("Failed to snap note {}: {}", label, e);
         }
-    }
+    });
 }
@@ -0,0 +1,52 @@
+//! The command-line subcommands, run as the built binary against a temporary data directory
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// An empty home directory, so the subcommands get their own ~/.peach-leaf
+fn temp_home(name: &str) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("peach-leaf-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(&home).unwrap();
+    home
+}
+
+fn run(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_app"))
+        .args(args)
+        .env("HOME", home)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn stdout_only_carries_command_output() {
+    let home = temp_home("stdout");
+
+    let created = run(&home, &["new", "--text", "# Groceries\n- milk"]);
+    assert!(created.status.success(), "new failed: {}", String::from_utf8_lossy(&created.stderr));
+    let note_id = String::from_utf8(created.stdout).unwrap().trim_end().to_string();
+    assert!(note_id.starts_with("note-") && !note_id.contains('\n'), "new printed {:?}", note_id);
+
+    let listed = run(&home, &["list", "--json"]);
+    assert!(listed.status.success(), "list failed: {}", String::from_utf8_lossy(&listed.stderr));
+    let notes: serde_json::Value = serde_json::from_slice(&listed.stdout)
+        .unwrap_or_else(|e| panic!("list --json printed more than JSON ({}): {}", e, String::from_utf8_lossy(&listed.stdout)));
+    assert_eq!(notes[0]["id"], note_id.as_str());
+    assert_eq!(notes[0]["title"], "Groceries");
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn headless_runs_do_not_advertise_themselves() {
+    let home = temp_home("headless");
+
+    let created = run(&home, &["new", "--text", "Call back"]);
+    assert!(created.status.success(), "new failed: {}", String::from_utf8_lossy(&created.stderr));
+    // instance.json tells later launches where to send their requests; a finished run takes none
+    assert!(!home.join(".peach-leaf").join("instance.json").exists());
+
+    let _ = fs::remove_dir_all(&home);
+}
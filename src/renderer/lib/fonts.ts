@@ -0,0 +1,21 @@
+// Font stacks for the font families stored in StickerData.fontFamily (see fonts.rs)
+const SYSTEM_FONT = '-apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, "Helvetica Neue", Arial, sans-serif';
+const SERIF_FONT = '"New York", "Iowan Old Style", Georgia, "Times New Roman", serif';
+const MONOSPACE_FONT = '"SF Mono", Menlo, Consolas, "Liberation Mono", monospace';
+
+export const DEFAULT_FONT_FAMILY = 'system';
+export const DEFAULT_LINE_HEIGHT = 1.6;
+
+/** CSS font-family for a stored family: a generic one, or an installed font with the system font as fallback */
+export function cssFontFamily(family: string | undefined): string {
+  switch (family ?? DEFAULT_FONT_FAMILY) {
+    case 'system':
+      return SYSTEM_FONT;
+    case 'serif':
+      return SERIF_FONT;
+    case 'monospace':
+      return MONOSPACE_FONT;
+    default:
+      return `"${family}", ${SYSTEM_FONT}`;
+  }
+}
@@ -12,6 +12,11 @@ export interface StickerData {
   textColor: string;
   mode: 'edit' | 'preview';
   fontSize?: number;
+  fontFamily?: string;
+  lineHeight?: number;
+  title?: string | null;
+  tags?: string[];
+  hidden?: boolean;
 }
 
 export const tauriAPI = {